@@ -0,0 +1,55 @@
+#![allow(clippy::needless_return)]
+
+extern crate criterion;
+extern crate vector_demo;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use vector_demo::eng::euler::*;
+
+fn affine_matrix() -> Mat4 {
+    return translation_matrix(3.0, -2.0, 7.5)
+        * axis_angle_rotation_matrix(Vec3::new(1.0, 2.0, -0.5), 1.1)
+        * scale_matrix(2.0, 0.5, 3.0);
+}
+
+fn bench_mat4_ops(c: &mut Criterion) {
+    let a = affine_matrix();
+    let b = y_rotation_matrix(0.4) * translation_matrix(1.0, 2.0, 3.0);
+    let vector = Vec4::new(1.0, -2.0, 3.0, 1.0);
+
+    let mut group = c.benchmark_group("mat4");
+    group.bench_function("mul_mat4", |bench| bench.iter(|| black_box(a) * black_box(b)));
+    group.bench_function("mul_vec4", |bench| bench.iter(|| black_box(a) * black_box(vector)));
+    group.bench_function("mul_scalar", |bench| bench.iter(|| black_box(a) * black_box(0.5)));
+    group.bench_function("upper_left", |bench| bench.iter(|| black_box(a).upper_left()));
+    group.bench_function("determinant", |bench| bench.iter(|| black_box(a).determinant()));
+    group.finish();
+}
+
+fn bench_inverse(c: &mut Criterion) {
+    let affine = affine_matrix();
+    let rigid = translation_matrix(3.0, -2.0, 7.5) * z_rotation_matrix(0.7);
+
+    let mut group = c.benchmark_group("inverse");
+    group.bench_function("inverse", |bench| bench.iter(|| black_box(affine).inverse()));
+    group.bench_function("try_inverse", |bench| bench.iter(|| black_box(affine).try_inverse(INVERSE_EPSILON)));
+    group.bench_function("try_affine_inverse", |bench| bench.iter(|| black_box(affine).try_affine_inverse(INVERSE_EPSILON)));
+    group.bench_function("rigid_inverse", |bench| bench.iter(|| black_box(rigid).rigid_inverse()));
+    group.bench_function("mat3_inverse", |bench| bench.iter(|| black_box(affine.upper_left()).inverse()));
+    group.finish();
+}
+
+fn bench_point_transform(c: &mut Criterion) {
+    let matrix = affine_matrix();
+    let point = Vec3::new(1.0, -2.0, 3.0);
+
+    let mut group = c.benchmark_group("transform");
+    group.bench_function("transform_point", |bench| bench.iter(|| black_box(matrix).transform_point(black_box(point))));
+    group.bench_function("transform_direction", |bench| bench.iter(|| black_box(matrix).transform_direction(black_box(point))));
+    group.bench_function("mat3_mul_vec3", |bench| bench.iter(|| black_box(matrix.upper_left()) * black_box(point)));
+    group.finish();
+}
+
+criterion_group!(benches, bench_mat4_ops, bench_inverse, bench_point_transform);
+criterion_main!(benches);
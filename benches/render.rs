@@ -0,0 +1,49 @@
+#![allow(clippy::needless_return)]
+
+extern crate criterion;
+extern crate vector_demo;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use vector_demo::eng::euler::*;
+use vector_demo::eng::renderer::{make_cube, make_tesseract, Camera, GameObject, Renderer};
+
+// A grid of cubes and tesseracts in front of the camera, all inside the frustum so none of them
+// are culled
+fn scene(count: usize) -> Vec<GameObject> {
+    let side = (count as f64).sqrt().ceil() as usize;
+    let spacing = 8.0;
+
+    return (0..count).map(|idx| {
+        let mut object = if idx % 2 == 0 { make_cube(4.0) } else { make_tesseract(4.0) };
+        let (row, col) = ((idx / side) as f64, (idx % side) as f64);
+
+        object.position = Vec3::new((col - side as f64 / 2.0) * spacing, (row - side as f64 / 2.0) * spacing, side as f64 * spacing / 2.0);
+        object.orientation = axis_angle_rotation_matrix(Vec3::new(1.0, idx as f64, 0.5), idx as f64 * 0.1);
+        object
+    }).collect();
+}
+
+fn bench_render_frame(c: &mut Criterion) {
+    let camera = Camera::default();
+    let mut group = c.benchmark_group("render_frame");
+    group.sample_size(20);
+
+    for &count in &[10usize, 100, 1000, 10000] {
+        let objects = scene(count);
+        let mut renderer = Renderer::default();
+
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::new("f64", count), &objects, |bench, objects| {
+            bench.iter(|| renderer.render_frame(black_box(objects), &camera))
+        });
+        group.bench_with_input(BenchmarkId::new("f32", count), &objects, |bench, objects| {
+            bench.iter(|| renderer.render_frame_with::<f32>(black_box(objects), &camera))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_render_frame);
+criterion_main!(benches);
@@ -0,0 +1,79 @@
+#![allow(clippy::needless_return)]
+
+extern crate criterion;
+extern crate vector_demo;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use vector_demo::eng::batch::BatchProject;
+use vector_demo::eng::euler::*;
+use vector_demo::eng::renderer::{make_sphere, Camera, GameObject, Renderer};
+
+fn scene_object(points: usize) -> GameObject {
+    // Rings times segments plus the two poles
+    let mut object = make_sphere(20.0, points / 32, 32);
+    object.orientation = axis_angle_rotation_matrix(Vec3::new(1.0, 2.0, 0.5), 0.8);
+    object.scale = Vec3::new(1.0, 1.5, 0.75);
+    object.position = Vec3::new(3.0, -2.0, 40.0);
+    return object;
+}
+
+// How `render_frame` used to transform points: one matrix at a time through `Mat4 * Vec4`
+fn project_per_point(renderer: &Renderer, camera: &Camera, object: &GameObject, out: &mut Vec<f64>) {
+    let translation = translation_matrix(object.position[0], object.position[1], object.position[2]);
+    let scale = scale_matrix(object.scale[0], object.scale[1], object.scale[2]);
+    let view = camera.view_matrix();
+
+    for point in &object.points {
+        let mut current_point = Vec4::new(point[0], point[1], point[2], 1.0);
+        current_point = scale * current_point;
+        current_point = object.orientation * current_point;
+        current_point = translation * current_point;
+        current_point = view * current_point;
+        current_point = renderer.PROJECTION_MATRIX * current_point;
+        current_point = current_point / current_point[3];
+
+        out.push(current_point[0]);
+        out.push(current_point[1]);
+    }
+}
+
+fn bench_projection(c: &mut Criterion) {
+    let renderer = Renderer::default();
+    let camera = Camera::default();
+    let mut group = c.benchmark_group("project_points");
+
+    for &points in &[64usize, 1024, 16384] {
+        let object = scene_object(points);
+        let mvp = renderer.PROJECTION_MATRIX * camera.view_matrix() * object.model_matrix();
+        let mvp_f32: Mat4<f32> = mvp.cast();
+        let mut out: Vec<f64> = Vec::with_capacity(object.points.len() * 2);
+        let mut out_f32: Vec<f32> = Vec::with_capacity(object.points.len() * 2);
+
+        group.throughput(Throughput::Elements(object.points.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("per_point", points), &object, |b, object| {
+            b.iter(|| {
+                out.clear();
+                project_per_point(&renderer, &camera, black_box(object), &mut out);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch_f64", points), &object, |b, object| {
+            b.iter(|| {
+                out.clear();
+                f64::project_points(black_box(&mvp), &black_box(object).points, &mut out);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch_f32", points), &object, |b, object| {
+            b.iter(|| {
+                out_f32.clear();
+                f32::project_points(black_box(&mvp_f32), &black_box(object).points, &mut out_f32);
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_projection);
+criterion_main!(benches);
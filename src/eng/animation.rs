@@ -0,0 +1,336 @@
+use super::error::Error;
+use super::euler::{Vec3, Quat};
+use super::physics::shift;
+use super::renderer::{GameObject, Camera, Renderer};
+
+// Shapes the progress between a keyframe and the next one
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Easing {
+    Step,
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    Bezier(f64, f64, f64, f64),          // CSS-style cubic-bezier(x1, y1, x2, y2)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LoopMode {
+    Once,
+    Loop,
+    PingPong,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Keyframe<T> {
+    pub time: f64,
+    pub value: T,
+    pub easing: Easing,                  // Used on the way to the following keyframe
+}
+
+#[derive(Clone, Debug)]
+pub struct Track<T> {
+    pub keyframes: Vec<Keyframe<T>>,     // Kept sorted by time
+    pub loop_mode: LoopMode,
+}
+
+pub trait Interpolate: Copy {
+    fn interpolate(self, other: Self, t: f64) -> Self;
+}
+
+impl Interpolate for f64 {
+    fn interpolate(self, other: f64, t: f64) -> f64 {
+        return self + (other - self) * t;
+    }
+}
+
+impl Interpolate for Vec3 {
+    fn interpolate(self, other: Vec3, t: f64) -> Vec3 {
+        return self + (other - self) * t;
+    }
+}
+
+impl Interpolate for Quat {
+    fn interpolate(self, other: Quat, t: f64) -> Quat {
+        return self.slerp(other, t);
+    }
+}
+
+impl Easing {
+    // Accepts "step", "linear", "ease_in", "ease_out", "ease_in_out" or "bezier(x1, y1, x2, y2)"
+    pub fn parse(name: &str) -> Result<Easing, Error> {
+        let name = name.trim();
+        let unknown = || Error::UnknownEasing(name.to_string());
+
+        if let Some(arguments) = name.strip_prefix("bezier(").and_then(|rest| rest.strip_suffix(')')) {
+            let values = arguments.split(',')
+                .map(|value| value.trim().parse::<f64>())
+                .collect::<Result<Vec::<f64>, _>>()
+                .map_err(|_| unknown())?;
+
+            if values.len() != 4 {
+                return Err(unknown());
+            }
+            return Ok(Easing::Bezier(values[0], values[1], values[2], values[3]));
+        }
+
+        return match name {
+            "step" => Ok(Easing::Step),
+            "linear" => Ok(Easing::Linear),
+            "ease_in" => Ok(Easing::EaseIn),
+            "ease_out" => Ok(Easing::EaseOut),
+            "ease_in_out" => Ok(Easing::EaseInOut),
+            _ => Err(unknown()),
+        };
+    }
+
+    pub fn apply(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+
+        return match *self {
+            Easing::Step => if t < 1.0 { 0.0 } else { 1.0 },
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Easing::Bezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, t),
+        };
+    }
+}
+
+// Finds the curve parameter whose x matches `x` by bisection, then returns the y at that parameter.
+// As in CSS, x1 and x2 are kept within [0, 1] so that x grows monotonically along the curve.
+fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, x: f64) -> f64 {
+    let (x1, x2) = (x1.clamp(0.0, 1.0), x2.clamp(0.0, 1.0));
+    let curve = |p1: f64, p2: f64, s: f64| {
+        let inv = 1.0 - s;
+        return 3.0 * inv * inv * s * p1 + 3.0 * inv * s * s * p2 + s * s * s;
+    };
+
+    let (mut low, mut high) = (0.0, 1.0);
+    let mut s = x;
+
+    for _ in 0..40 {
+        let estimate = curve(x1, x2, s);
+
+        if (estimate - x).abs() < 1e-7 {
+            break;
+        }
+        if estimate < x {
+            low = s;
+        } else {
+            high = s;
+        }
+        s = (low + high) / 2.0;
+    }
+
+    return curve(y1, y2, s);
+}
+
+impl<T: Interpolate> Track<T> {
+    pub fn new(loop_mode: LoopMode) -> Track<T> {
+        return Track {keyframes: Vec::new(), loop_mode: loop_mode};
+    }
+
+    // Replaces any keyframe already at `time`
+    pub fn insert(&mut self, time: f64, value: T, easing: Easing) {
+        let keyframe = Keyframe {time: time, value: value, easing: easing};
+        let idx = self.keyframes.partition_point(|existing| existing.time < time);
+
+        if idx < self.keyframes.len() && self.keyframes[idx].time == time {
+            self.keyframes[idx] = keyframe;
+        } else {
+            self.keyframes.insert(idx, keyframe);
+        }
+    }
+
+    pub fn start(&self) -> f64 {
+        return self.keyframes.first().map_or(0.0, |keyframe| keyframe.time);
+    }
+
+    pub fn end(&self) -> f64 {
+        return self.keyframes.last().map_or(0.0, |keyframe| keyframe.time);
+    }
+
+    // Maps global time onto the span of the keyframes according to the loop mode
+    fn local_time(&self, time: f64) -> f64 {
+        let (start, end) = (self.start(), self.end());
+        let duration = end - start;
+
+        if duration <= 0.0 {
+            return start;
+        }
+
+        return match self.loop_mode {
+            LoopMode::Once => time.clamp(start, end),
+            LoopMode::Loop => start + (time - start).rem_euclid(duration),
+            LoopMode::PingPong => {
+                let phase = (time - start).rem_euclid(2.0 * duration);
+                start + if phase <= duration { phase } else { 2.0 * duration - phase }
+            },
+        };
+    }
+
+    pub fn sample(&self, time: f64) -> Option<T> {
+        if self.keyframes.is_empty() {
+            return None;
+        }
+
+        let time = self.local_time(time);
+        let next = self.keyframes.partition_point(|keyframe| keyframe.time <= time);
+
+        if next == 0 {
+            return Some(self.keyframes[0].value);
+        }
+        if next == self.keyframes.len() {
+            return Some(self.keyframes[next - 1].value);
+        }
+
+        let (from, to) = (&self.keyframes[next - 1], &self.keyframes[next]);
+        let progress = (time - from.time) / (to.time - from.time);
+
+        return Some(from.value.interpolate(to.value, from.easing.apply(progress)));
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ObjectAnimation {
+    pub object_id: usize,
+    pub position: Track<Vec3>,
+    pub orientation: Track<Quat>,
+    pub scale: Track<Vec3>,
+}
+
+#[derive(Clone, Debug)]
+pub struct CameraAnimation {
+    pub position: Track<Vec3>,
+    pub orientation: Track<Quat>,
+    pub fov: Track<f64>,
+}
+
+// Tracks only override the properties they have keyframes for, so an object can be animated
+// in position while still spinning from its `angular_velocity`
+#[derive(Clone, Debug)]
+pub struct Animator {
+    pub time: f64,
+    pub speed: f64,                      // Ticks of animation time per `advance`
+    pub playing: bool,
+    pub objects: Vec<ObjectAnimation>,
+    pub camera: CameraAnimation,
+}
+
+impl ObjectAnimation {
+    pub fn new(object_id: usize) -> ObjectAnimation {
+        return ObjectAnimation {
+            object_id: object_id,
+            position: Track::new(LoopMode::Once),
+            orientation: Track::new(LoopMode::Once),
+            scale: Track::new(LoopMode::Once),
+        };
+    }
+
+    pub fn set_loop_mode(&mut self, loop_mode: LoopMode) {
+        self.position.loop_mode = loop_mode;
+        self.orientation.loop_mode = loop_mode;
+        self.scale.loop_mode = loop_mode;
+    }
+
+    // Moves through `shift` so a body's Verlet history follows and the move adds no velocity
+    pub fn apply(&self, object: &mut GameObject, time: f64) {
+        if let Some(position) = self.position.sample(time) {
+            let delta = position - object.position;
+            shift(object, delta);
+        }
+        if let Some(orientation) = self.orientation.sample(time) {
+            object.orientation = orientation.to_matrix();
+        }
+        if let Some(scale) = self.scale.sample(time) {
+            object.scale = scale;
+        }
+    }
+}
+
+impl CameraAnimation {
+    pub fn default() -> CameraAnimation {
+        return CameraAnimation {
+            position: Track::new(LoopMode::Once),
+            orientation: Track::new(LoopMode::Once),
+            fov: Track::new(LoopMode::Once),
+        };
+    }
+
+    pub fn set_loop_mode(&mut self, loop_mode: LoopMode) {
+        self.position.loop_mode = loop_mode;
+        self.orientation.loop_mode = loop_mode;
+        self.fov.loop_mode = loop_mode;
+    }
+
+    pub fn apply(&self, camera: &mut Camera, renderer: &mut Renderer, time: f64) {
+        if let Some(position) = self.position.sample(time) {
+            camera.position = position;
+        }
+        if let Some(orientation) = self.orientation.sample(time) {
+            camera.orientation = orientation.to_matrix();
+        }
+        if let Some(fov) = self.fov.sample(time) {
+            renderer.set_fov(fov);
+        }
+    }
+}
+
+impl LoopMode {
+    // Accepts "once", "loop" or "ping_pong"
+    pub fn parse(name: &str) -> Option<LoopMode> {
+        return match name {
+            "once" => Some(LoopMode::Once),
+            "loop" => Some(LoopMode::Loop),
+            "ping_pong" => Some(LoopMode::PingPong),
+            _ => None,
+        };
+    }
+}
+
+impl Animator {
+    pub fn default() -> Animator {
+        return Animator {
+            time: 0.0,
+            speed: 1.0,
+            playing: true,
+            objects: Vec::<ObjectAnimation>::new(),
+            camera: CameraAnimation::default(),
+        };
+    }
+
+    // Creates the animation for `object_id` on first use
+    pub fn object(&mut self, object_id: usize) -> &mut ObjectAnimation {
+        let idx = match self.objects.iter().position(|animation| animation.object_id == object_id) {
+            Some(idx) => idx,
+            None => {
+                self.objects.push(ObjectAnimation::new(object_id));
+                self.objects.len() - 1
+            },
+        };
+
+        return &mut self.objects[idx];
+    }
+
+    pub fn advance(&mut self) {
+        if self.playing {
+            self.time += self.speed;
+        }
+    }
+
+    pub fn seek(&mut self, time: f64) {
+        self.time = time;
+    }
+
+    pub fn apply(&self, objects: &mut [GameObject], camera: &mut Camera, renderer: &mut Renderer) {
+        for animation in &self.objects {
+            if let Some(object) = objects.get_mut(animation.object_id) {
+                animation.apply(object, self.time);
+            }
+        }
+
+        self.camera.apply(camera, renderer, self.time);
+    }
+}
@@ -0,0 +1,100 @@
+use super::euler::{Vec3, Mat4, Scalar};
+
+// Projects whole point lists through one combined model-view-projection matrix instead of
+// transforming each point through every matrix in turn. On wasm builds with `simd128` enabled
+// (RUSTFLAGS="-C target-feature=+simd128") the matrix columns are kept in vector registers;
+// everywhere else the scalar loop below is used. tests/simd.rs checks that the two agree.
+
+pub trait BatchProject: Scalar {
+    // Appends x / w and y / w for every point to `out`
+    fn project_points(mvp: &Mat4<Self>, points: &[Vec3], out: &mut Vec<Self>);
+}
+
+pub fn project_points_scalar<T: Scalar>(mvp: &Mat4<T>, points: &[Vec3], out: &mut Vec<T>) {
+    let m = mvp.elems;
+    out.reserve(points.len() * 2);
+
+    for point in points {
+        let (x, y, z) = (T::from_f64(point[0]), T::from_f64(point[1]), T::from_f64(point[2]));
+
+        let clip_x = m[0] * x + m[1] * y + m[2] * z + m[3];
+        let clip_y = m[4] * x + m[5] * y + m[6] * z + m[7];
+        let clip_w = m[12] * x + m[13] * y + m[14] * z + m[15];
+
+        out.push(clip_x / clip_w);
+        out.push(clip_y / clip_w);
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod simd {
+    use core::arch::wasm32::*;
+
+    use super::{Mat4, Vec3, Scalar};
+
+    // Column `col` of the matrix, i.e. where the basis vector of that axis ends up
+    fn column<T: Scalar>(matrix: &Mat4<T>, col: usize) -> [T; 4] {
+        return [matrix[0][col], matrix[1][col], matrix[2][col], matrix[3][col]];
+    }
+
+    // Two f64 lanes per register: (x, y) and (z, w) of the clip-space point
+    pub fn project_points_f64(mvp: &Mat4<f64>, points: &[Vec3], out: &mut Vec<f64>) {
+        let columns = [column(mvp, 0), column(mvp, 1), column(mvp, 2), column(mvp, 3)];
+        let low = columns.map(|col| f64x2(col[0], col[1]));
+        let high = columns.map(|col| f64x2(col[2], col[3]));
+
+        out.reserve(points.len() * 2);
+
+        for point in points {
+            let (x, y, z) = (f64x2_splat(point[0]), f64x2_splat(point[1]), f64x2_splat(point[2]));
+
+            let xy = f64x2_add(f64x2_add(f64x2_mul(low[0], x), f64x2_mul(low[1], y)), f64x2_add(f64x2_mul(low[2], z), low[3]));
+            let zw = f64x2_add(f64x2_add(f64x2_mul(high[0], x), f64x2_mul(high[1], y)), f64x2_add(f64x2_mul(high[2], z), high[3]));
+
+            let projected = f64x2_div(xy, f64x2_splat(f64x2_extract_lane::<1>(zw)));
+            out.push(f64x2_extract_lane::<0>(projected));
+            out.push(f64x2_extract_lane::<1>(projected));
+        }
+    }
+
+    // The whole clip-space point in one f32x4 register
+    pub fn project_points_f32(mvp: &Mat4<f32>, points: &[Vec3], out: &mut Vec<f32>) {
+        let columns = [column(mvp, 0), column(mvp, 1), column(mvp, 2), column(mvp, 3)].map(|col| f32x4(col[0], col[1], col[2], col[3]));
+
+        out.reserve(points.len() * 2);
+
+        for point in points {
+            let (x, y, z) = (f32x4_splat(point[0] as f32), f32x4_splat(point[1] as f32), f32x4_splat(point[2] as f32));
+
+            let clip = f32x4_add(f32x4_add(f32x4_mul(columns[0], x), f32x4_mul(columns[1], y)), f32x4_add(f32x4_mul(columns[2], z), columns[3]));
+            let w = f32x4_extract_lane::<3>(clip);
+
+            out.push(f32x4_extract_lane::<0>(clip) / w);
+            out.push(f32x4_extract_lane::<1>(clip) / w);
+        }
+    }
+}
+
+impl BatchProject for f64 {
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    fn project_points(mvp: &Mat4<f64>, points: &[Vec3], out: &mut Vec<f64>) {
+        simd::project_points_f64(mvp, points, out);
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", target_feature = "simd128")))]
+    fn project_points(mvp: &Mat4<f64>, points: &[Vec3], out: &mut Vec<f64>) {
+        project_points_scalar(mvp, points, out);
+    }
+}
+
+impl BatchProject for f32 {
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    fn project_points(mvp: &Mat4<f32>, points: &[Vec3], out: &mut Vec<f32>) {
+        simd::project_points_f32(mvp, points, out);
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", target_feature = "simd128")))]
+    fn project_points(mvp: &Mat4<f32>, points: &[Vec3], out: &mut Vec<f32>) {
+        project_points_scalar(mvp, points, out);
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+
+// Post-processing of the line buffer for XY vector monitors. Segments that share endpoints are
+// chained into polylines so the beam can draw them without lifting, and the polylines are then
+// ordered so the blanked moves between them are as short as possible.
+
+#[derive(Clone, Debug)]
+pub struct Polyline {
+    pub points: Vec<(f64, f64)>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BeamSettings {
+    pub enabled: bool,
+    pub join_tolerance: f64,             // Endpoints closer than this are treated as the same point
+    pub two_opt_passes: usize,           // Zero keeps the greedy nearest-neighbour order
+    pub start: (f64, f64),               // Where the beam rests between refreshes
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BeamStats {
+    pub polylines: usize,
+    pub segments: usize,
+    pub draw_distance: f64,
+    pub blank_distance: f64,             // Includes the move back to `start` after the last polyline
+}
+
+// Number of f64s per point in the flattened path: x, y and 1 to draw up to it or 0 to blank
+pub const BEAM_POINT_STRIDE: usize = 3;
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    return ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+}
+
+impl Polyline {
+    pub fn start(&self) -> (f64, f64) {
+        return self.points[0];
+    }
+
+    pub fn end(&self) -> (f64, f64) {
+        return self.points[self.points.len() - 1];
+    }
+
+    pub fn is_closed(&self) -> bool {
+        return self.points.len() > 2 && self.start() == self.end();
+    }
+
+    pub fn length(&self) -> f64 {
+        return self.points.windows(2).map(|pair| distance(pair[0], pair[1])).sum();
+    }
+
+    pub fn reverse(&mut self) {
+        self.points.reverse();
+    }
+
+    // Starts a closed loop at the vertex nearest to `point` instead
+    fn rotate_to(&mut self, point: (f64, f64)) {
+        let nearest = (0..self.points.len() - 1)
+            .min_by(|&i, &j| distance(self.points[i], point).total_cmp(&distance(self.points[j], point)))
+            .unwrap_or(0);
+
+        if nearest != 0 {
+            let mut points: Vec::<(f64, f64)> = self.points[nearest..self.points.len() - 1].to_vec();
+            points.extend_from_slice(&self.points[..=nearest]);
+            self.points = points;
+        }
+    }
+}
+
+impl BeamSettings {
+    pub fn default() -> BeamSettings {
+        return BeamSettings {enabled: false, join_tolerance: 1e-6, two_opt_passes: 4, start: (0.0, 0.0)};
+    }
+}
+
+// Joins segments from a flat tail_x, tail_y, head_x, head_y buffer into polylines, starting new
+// chains at odd-degree points first so open paths are walked end to end
+pub fn chain_segments(lines: &[f64], tolerance: f64) -> Vec::<Polyline> {
+    let tolerance = tolerance.max(1e-12);
+    let mut cells: HashMap<(i64, i64), Vec::<usize>> = HashMap::new();
+    let mut positions: Vec::<(f64, f64)> = Vec::new();
+    let mut adjacency: Vec::<Vec::<(usize, usize)>> = Vec::new();
+    let mut edge_count: usize = 0;
+
+    // Cells are `tolerance` wide, so any point close enough is in this cell or a neighbouring one
+    let mut node = |point: (f64, f64), positions: &mut Vec::<(f64, f64)>, adjacency: &mut Vec::<Vec::<(usize, usize)>>| {
+        let cell = ((point.0 / tolerance).floor() as i64, (point.1 / tolerance).floor() as i64);
+
+        let mut nearest: Option<(usize, f64)> = None;
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for &idx in cells.get(&(cell.0 + dx, cell.1 + dy)).into_iter().flatten() {
+                    let gap = distance(positions[idx], point);
+                    if gap <= tolerance && nearest.is_none_or(|(_, best)| gap < best) {
+                        nearest = Some((idx, gap));
+                    }
+                }
+            }
+        }
+
+        if let Some((idx, _)) = nearest {
+            return idx;
+        }
+
+        positions.push(point);
+        adjacency.push(Vec::new());
+        cells.entry(cell).or_default().push(positions.len() - 1);
+        return positions.len() - 1;
+    };
+
+    for line in lines.chunks_exact(4) {
+        let tail = node((line[0], line[1]), &mut positions, &mut adjacency);
+        let head = node((line[2], line[3]), &mut positions, &mut adjacency);
+
+        adjacency[tail].push((edge_count, head));
+        if head != tail {
+            adjacency[head].push((edge_count, tail));
+        }
+        edge_count += 1;
+    }
+
+    let mut used = vec![false; edge_count];
+    let mut polylines: Vec::<Polyline> = Vec::new();
+
+    let odd_nodes = (0..positions.len()).filter(|&idx| adjacency[idx].len() % 2 == 1);
+    let start_nodes: Vec::<usize> = odd_nodes.chain(0..positions.len()).collect();
+
+    for start in start_nodes {
+        while adjacency[start].iter().any(|(edge, _)| !used[*edge]) {
+            let mut current = start;
+            let mut points = vec![positions[start]];
+
+            while let Some(&(edge, next)) = adjacency[current].iter().find(|(edge, _)| !used[*edge]) {
+                used[edge] = true;
+                points.push(positions[next]);
+                current = next;
+            }
+
+            polylines.push(Polyline {points: points});
+        }
+    }
+
+    return polylines;
+}
+
+// Greedy nearest-neighbour tour over the polylines, entering each from whichever end is closer
+pub fn order_greedy(mut polylines: Vec::<Polyline>, start: (f64, f64)) -> Vec::<Polyline> {
+    let mut ordered: Vec::<Polyline> = Vec::with_capacity(polylines.len());
+    let mut position = start;
+
+    while !polylines.is_empty() {
+        let mut best = (0, f64::INFINITY, false);
+
+        for (idx, polyline) in polylines.iter().enumerate() {
+            let to_start = distance(position, polyline.start());
+            let to_end = distance(position, polyline.end());
+
+            if to_start < best.1 {
+                best = (idx, to_start, false);
+            }
+            if to_end < best.1 {
+                best = (idx, to_end, true);
+            }
+        }
+
+        let mut next = polylines.swap_remove(best.0);
+        if next.is_closed() {
+            next.rotate_to(position);
+        } else if best.2 {
+            next.reverse();
+        }
+
+        position = next.end();
+        ordered.push(next);
+    }
+
+    return ordered;
+}
+
+// 2-opt over the tour: reversing a run of polylines (and each polyline in it) when that
+// shortens the two blank moves at its ends. A run reaching the end of the tour counts the move
+// back to `start`, as `measure` does.
+pub fn improve_two_opt(polylines: &mut [Polyline], start: (f64, f64), passes: usize) {
+    let count = polylines.len();
+
+    for _ in 0..passes {
+        let mut improved = false;
+
+        for i in 0..count {
+            let before = if i == 0 { start } else { polylines[i - 1].end() };
+
+            for j in (i + 1)..count {
+                let after = if j + 1 < count { polylines[j + 1].start() } else { start };
+
+                let current = distance(before, polylines[i].start()) + distance(polylines[j].end(), after);
+                let swapped = distance(before, polylines[j].end()) + distance(polylines[i].start(), after);
+
+                if swapped + 1e-12 < current {
+                    polylines[i..=j].reverse();
+                    for polyline in &mut polylines[i..=j] {
+                        polyline.reverse();
+                    }
+                    improved = true;
+                }
+            }
+        }
+
+        if !improved {
+            break;
+        }
+    }
+}
+
+pub fn measure(polylines: &[Polyline], start: (f64, f64)) -> BeamStats {
+    let mut stats = BeamStats {polylines: polylines.len(), segments: 0, draw_distance: 0.0, blank_distance: 0.0};
+    let mut position = start;
+
+    for polyline in polylines {
+        stats.segments += polyline.points.len() - 1;
+        stats.draw_distance += polyline.length();
+        stats.blank_distance += distance(position, polyline.start());
+        position = polyline.end();
+    }
+
+    stats.blank_distance += distance(position, start);
+    return stats;
+}
+
+// Chains and orders a frame, returning the flattened path and its statistics
+pub fn optimize(lines: &[f64], settings: &BeamSettings) -> (Vec::<f64>, BeamStats) {
+    let mut polylines = order_greedy(chain_segments(lines, settings.join_tolerance), settings.start);
+    improve_two_opt(&mut polylines, settings.start, settings.two_opt_passes);
+
+    let mut path: Vec::<f64> = Vec::new();
+    for polyline in &polylines {
+        for (idx, point) in polyline.points.iter().enumerate() {
+            path.push(point.0);
+            path.push(point.1);
+            path.push(if idx == 0 { 0.0 } else { 1.0 });
+        }
+    }
+
+    return (path, measure(&polylines, settings.start));
+}
+
+// A beam path with every segment drawn in buffer order, blanking to a segment's tail only when
+// it does not continue from where the previous one ended
+pub fn unoptimized_path(lines: &[f64]) -> Vec::<f64> {
+    let mut path: Vec::<f64> = Vec::new();
+    let mut position: Option<(f64, f64)> = None;
+
+    for line in lines.chunks_exact(4) {
+        if position != Some((line[0], line[1])) {
+            path.extend_from_slice(&[line[0], line[1], 0.0]);
+        }
+        path.extend_from_slice(&[line[2], line[3], 1.0]);
+        position = Some((line[2], line[3]));
+    }
+
+    return path;
+}
@@ -0,0 +1,114 @@
+use super::euler::{Vec3, Mat4};
+
+#[derive(Clone, Copy, Debug)]
+pub struct BoundingSphere {
+    pub center: Vec3,
+    pub radius: f64,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Aabb {
+        return Aabb {min: min, max: max};
+    }
+
+    pub fn default() -> Aabb {
+        return Aabb {min: Vec3::default(), max: Vec3::default()};
+    }
+
+    pub fn from_points(points: &[Vec3]) -> Aabb {
+        if points.is_empty() {
+            return Aabb::default();
+        }
+
+        let mut min = points[0];
+        let mut max = points[0];
+
+        for point in points {
+            for axis in 0..3 {
+                min.elems[axis] = min[axis].min(point[axis]);
+                max.elems[axis] = max[axis].max(point[axis]);
+            }
+        }
+
+        return Aabb {min: min, max: max};
+    }
+
+    pub fn center(&self) -> Vec3 {
+        return (self.min + self.max) * 0.5;
+    }
+
+    pub fn extents(&self) -> Vec3 {
+        return (self.max - self.min) * 0.5;
+    }
+
+    pub fn contains(&self, point: Vec3) -> bool {
+        return (0..3).all(|axis| point[axis] >= self.min[axis] && point[axis] <= self.max[axis]);
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        return (0..3).all(|axis| self.min[axis] <= other.max[axis] && self.max[axis] >= other.min[axis]);
+    }
+
+    // Bounds of the eight transformed corners, so rotations grow the box rather than clip it
+    pub fn transform(&self, matrix: &Mat4) -> Aabb {
+        let mut corners: Vec::<Vec3> = Vec::<Vec3>::with_capacity(8);
+
+        for corner in 0..8 {
+            let x = if corner & 1 == 0 { self.min[0] } else { self.max[0] };
+            let y = if corner & 2 == 0 { self.min[1] } else { self.max[1] };
+            let z = if corner & 4 == 0 { self.min[2] } else { self.max[2] };
+
+            corners.push(matrix.transform_point(Vec3::new(x, y, z)));
+        }
+
+        return Aabb::from_points(&corners);
+    }
+}
+
+impl BoundingSphere {
+    pub fn new(center: Vec3, radius: f64) -> BoundingSphere {
+        return BoundingSphere {center: center, radius: radius};
+    }
+
+    pub fn default() -> BoundingSphere {
+        return BoundingSphere {center: Vec3::default(), radius: 0.0};
+    }
+
+    // Centered on the AABB of the points, which is tighter than the origin for off-center models
+    pub fn from_points(points: &[Vec3]) -> BoundingSphere {
+        let center = Aabb::from_points(points).center();
+        let mut radius_squared: f64 = 0.0;
+
+        for point in points {
+            let offset = *point - center;
+            radius_squared = radius_squared.max(offset.dot(offset));
+        }
+
+        return BoundingSphere {center: center, radius: radius_squared.sqrt()};
+    }
+
+    pub fn contains(&self, point: Vec3) -> bool {
+        let offset = point - self.center;
+        return offset.dot(offset) <= self.radius * self.radius;
+    }
+
+    pub fn intersects(&self, other: &BoundingSphere) -> bool {
+        let offset = other.center - self.center;
+        let reach = self.radius + other.radius;
+        return offset.dot(offset) <= reach * reach;
+    }
+
+    // The radius is scaled by the largest axis scale of the matrix so the sphere stays conservative
+    pub fn transform(&self, matrix: &Mat4) -> BoundingSphere {
+        return BoundingSphere {
+            center: matrix.transform_point(self.center),
+            radius: self.radius * matrix.max_axis_scale(),
+        };
+    }
+}
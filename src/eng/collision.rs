@@ -0,0 +1,244 @@
+use super::euler::Vec3;
+use super::bounds::{BoundingSphere, Aabb};
+use super::renderer::GameObject;
+
+// Normals point from object `a` towards object `b`
+#[derive(Clone, Copy, Debug)]
+pub struct Contact {
+    pub a: usize,
+    pub b: usize,
+    pub point: Vec3,
+    pub normal: Vec3,
+    pub depth: f64,
+}
+
+// Number of f64s each contact takes up once flattened for JS: a, b, point, normal, depth
+pub const CONTACT_STRIDE: usize = 9;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NarrowPhase {
+    Off,                                 // No detection at all, the default
+    Sphere,
+    Aabb,
+    Edges,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CollisionDetector {
+    pub narrow_phase: NarrowPhase,
+    pub edge_tolerance: f64,             // How close two wireframe edges must pass to touch
+}
+
+impl Contact {
+    pub fn flatten_into(&self, buffer: &mut Vec::<f64>) {
+        buffer.push(self.a as f64);
+        buffer.push(self.b as f64);
+        buffer.extend_from_slice(&self.point.elems);
+        buffer.extend_from_slice(&self.normal.elems);
+        buffer.push(self.depth);
+    }
+}
+
+pub fn sphere_sphere(a: &BoundingSphere, b: &BoundingSphere) -> Option<(Vec3, Vec3, f64)> {
+    let offset = b.center - a.center;
+    let distance = offset.length();
+    let depth = a.radius + b.radius - distance;
+
+    if depth < 0.0 {
+        return None;
+    }
+
+    let normal = if distance > 0.0 { offset / distance } else { Vec3::new(0.0, 1.0, 0.0) };
+    let point = a.center + normal * (a.radius - depth / 2.0);
+
+    return Some((point, normal, depth));
+}
+
+// Separates along the axis of least overlap
+pub fn aabb_aabb(a: &Aabb, b: &Aabb) -> Option<(Vec3, Vec3, f64)> {
+    if !a.intersects(b) {
+        return None;
+    }
+
+    let mut best_axis: usize = 0;
+    let mut depth: f64 = f64::INFINITY;
+
+    for axis in 0..3 {
+        let overlap = a.max[axis].min(b.max[axis]) - a.min[axis].max(b.min[axis]);
+        if overlap < depth {
+            depth = overlap;
+            best_axis = axis;
+        }
+    }
+
+    let mut normal = Vec3::default();
+    normal.elems[best_axis] = if b.center()[best_axis] >= a.center()[best_axis] { 1.0 } else { -1.0 };
+
+    let overlap_box = Aabb::new(
+        Vec3::new(a.min[0].max(b.min[0]), a.min[1].max(b.min[1]), a.min[2].max(b.min[2])),
+        Vec3::new(a.max[0].min(b.max[0]), a.max[1].min(b.max[1]), a.max[2].min(b.max[2])),
+    );
+
+    return Some((overlap_box.center(), normal, depth));
+}
+
+// Closest points between segments p1-q1 and p2-q2 (Ericson, Real-Time Collision Detection 5.1.9)
+pub fn closest_points_segments(p1: Vec3, q1: Vec3, p2: Vec3, q2: Vec3) -> (Vec3, Vec3) {
+    let d1 = q1 - p1;
+    let d2 = q2 - p2;
+    let r = p1 - p2;
+    let a = d1.dot(d1);
+    let e = d2.dot(d2);
+    let f = d2.dot(r);
+    let epsilon = 1e-12;
+
+    let (s, t);
+
+    if a <= epsilon && e <= epsilon {
+        return (p1, p2);
+    }
+
+    if a <= epsilon {
+        s = 0.0;
+        t = (f / e).clamp(0.0, 1.0);
+    } else {
+        let c = d1.dot(r);
+
+        if e <= epsilon {
+            t = 0.0;
+            s = (-c / a).clamp(0.0, 1.0);
+        } else {
+            let b = d1.dot(d2);
+            let denom = a * e - b * b;
+
+            let mut s_candidate = if denom != 0.0 { ((b * f - c * e) / denom).clamp(0.0, 1.0) } else { 0.0 };
+            let mut t_candidate = (b * s_candidate + f) / e;
+
+            if t_candidate < 0.0 {
+                t_candidate = 0.0;
+                s_candidate = (-c / a).clamp(0.0, 1.0);
+            } else if t_candidate > 1.0 {
+                t_candidate = 1.0;
+                s_candidate = ((b - c) / a).clamp(0.0, 1.0);
+            }
+
+            s = s_candidate;
+            t = t_candidate;
+        }
+    }
+
+    return (p1 + d1 * s, p2 + d2 * t);
+}
+
+// Finds the closest pair of wireframe edges between the two objects and reports a contact
+// when they pass within `tolerance` of each other
+pub fn edges_edges(a: &GameObject, b: &GameObject, tolerance: f64) -> Option<(Vec3, Vec3, f64)> {
+    let edges_a = world_edges(a);
+    let edges_b = world_edges(b);
+
+    let mut closest: Option<(Vec3, Vec3, f64)> = None;
+
+    for (tail_a, head_a) in &edges_a {
+        for (tail_b, head_b) in &edges_b {
+            let (on_a, on_b) = closest_points_segments(*tail_a, *head_a, *tail_b, *head_b);
+            let distance = (on_b - on_a).length();
+
+            let is_closer = match closest {
+                Some((_, _, best)) => distance < best,
+                None => true,
+            };
+
+            if distance <= tolerance && is_closer {
+                closest = Some((on_a, on_b, distance));
+            }
+        }
+    }
+
+    return closest.map(|(on_a, on_b, distance)| {
+        let normal = if distance > 0.0 { (on_b - on_a) / distance } else { Vec3::new(0.0, 1.0, 0.0) };
+        return ((on_a + on_b) * 0.5, normal, tolerance - distance);
+    });
+}
+
+fn world_edges(object: &GameObject) -> Vec::<(Vec3, Vec3)> {
+    let model = object.model_matrix();
+    let points: Vec::<Vec3> = object.points.iter().map(|point| model.transform_point(*point)).collect();
+
+    return object.connections.chunks_exact(2)
+        .filter(|pair| pair[0] < points.len() && pair[1] < points.len())
+        .map(|pair| (points[pair[0]], points[pair[1]]))
+        .collect();
+}
+
+// Broad phase: sorts boxes by their minimum x and only pairs boxes whose x intervals overlap
+pub fn sweep_and_prune(boxes: &[Aabb]) -> Vec::<(usize, usize)> {
+    let mut order: Vec::<usize> = (0..boxes.len()).collect();
+    order.sort_by(|&i, &j| boxes[i].min[0].total_cmp(&boxes[j].min[0]));
+
+    let mut pairs: Vec::<(usize, usize)> = Vec::<(usize, usize)>::new();
+    let mut active: Vec::<usize> = Vec::<usize>::new();
+
+    for &current in &order {
+        active.retain(|&other| boxes[other].max[0] >= boxes[current].min[0]);
+
+        for &other in &active {
+            if boxes[current].intersects(&boxes[other]) {
+                pairs.push((current.min(other), current.max(other)));
+            }
+        }
+
+        active.push(current);
+    }
+
+    pairs.sort_unstable();
+    return pairs;
+}
+
+impl CollisionDetector {
+    pub fn new(narrow_phase: NarrowPhase, edge_tolerance: f64) -> CollisionDetector {
+        return CollisionDetector {narrow_phase: narrow_phase, edge_tolerance: edge_tolerance};
+    }
+
+    pub fn default() -> CollisionDetector {
+        return CollisionDetector {narrow_phase: NarrowPhase::Off, edge_tolerance: 1.0};
+    }
+
+    // Every object with points takes part, bodies or not, so bullets and asteroids moved by
+    // `tick` still report their hits; `PhysicsWorld::resolve` ignores pairs without a body.
+    // Contacts refer to objects by their index in `objects`.
+    pub fn detect(&self, objects: &[GameObject]) -> Vec::<Contact> {
+        if self.narrow_phase == NarrowPhase::Off {
+            return Vec::new();
+        }
+
+        let margin = if self.narrow_phase == NarrowPhase::Edges { self.edge_tolerance } else { 0.0 };
+        let solids: Vec::<usize> = (0..objects.len())
+            .filter(|&idx| !objects[idx].points.is_empty())
+            .collect();
+
+        let boxes: Vec::<Aabb> = solids.iter().map(|&idx| {
+            let world_box = objects[idx].world_aabb();
+            let padding = Vec3::new(margin, margin, margin);
+            return Aabb::new(world_box.min - padding, world_box.max + padding);
+        }).collect();
+
+        let mut contacts: Vec::<Contact> = Vec::<Contact>::new();
+
+        for (box_a, box_b) in sweep_and_prune(&boxes) {
+            let (a, b) = (solids[box_a], solids[box_b]);
+
+            let result = match self.narrow_phase {
+                NarrowPhase::Off => None,
+                NarrowPhase::Sphere => sphere_sphere(&objects[a].world_bounding_sphere(), &objects[b].world_bounding_sphere()),
+                NarrowPhase::Aabb => aabb_aabb(&boxes[box_a], &boxes[box_b]),
+                NarrowPhase::Edges => edges_edges(&objects[a], &objects[b], self.edge_tolerance),
+            };
+
+            if let Some((point, normal, depth)) = result {
+                contacts.push(Contact {a: a, b: b, point: point, normal: normal, depth: depth});
+            }
+        }
+
+        return contacts;
+    }
+}
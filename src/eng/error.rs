@@ -0,0 +1,50 @@
+use std::fmt;
+
+// Failures reported by the engine instead of panicking. The wasm wrapper turns these into
+// exceptions carrying the `Display` text.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    UnknownShape(String),
+    UnknownObject(usize),
+    RiggedObject(usize),                 // Its skeleton rebuilds the mesh every frame, so it cannot morph
+    UnknownAlignment(String),
+    UnknownEasing(String),
+    NoSegments,                          // A wireframe with nothing to draw where lines are needed
+    OddConnectionCount(usize),           // `connections` must hold pairs of point indices
+    InvalidConnection {
+        connection: usize,               // Position in `connections`
+        index: usize,
+        point_count: usize,
+    },
+    Parse {
+        line: usize,                     // 1-based, as shown in an editor
+        message: String,
+    },
+    Io(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            Error::UnknownShape(name) => write!(formatter, "unknown shape \"{}\"", name),
+            Error::UnknownObject(object_id) => write!(formatter, "no object with ID {}", object_id),
+            Error::RiggedObject(object_id) => write!(formatter, "object {} is posed by a skeleton and cannot take morph targets", object_id),
+            Error::UnknownAlignment(alignment) => write!(formatter, "unknown text alignment \"{}\"", alignment),
+            Error::UnknownEasing(easing) => write!(formatter, "unknown easing \"{}\"", easing),
+            Error::NoSegments => write!(formatter, "the wireframe has no segments"),
+            Error::OddConnectionCount(count) => write!(formatter, "{} connection indices do not form pairs", count),
+            Error::InvalidConnection {connection, index, point_count} => write!(formatter,
+                "connection {} refers to point {} but the object has {} points", connection, index, point_count),
+            Error::Parse {line, message} => write!(formatter, "line {}: {}", line, message),
+            Error::Io(message) => write!(formatter, "{}", message),
+        };
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Error {
+        return Error::Io(error.to_string());
+    }
+}
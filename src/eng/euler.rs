@@ -1,84 +1,180 @@
-use std::ops::{Index, Add, Sub, Mul, Div};
-use std::io;
-use std::io::Write;
+use std::fmt::{self, Debug, Display};
+use std::ops::{Index, Add, Sub, Mul, Div, Neg};
+
+// Floating-point element type of the vectors and matrices. Everything defaults to f64; f32
+// halves the size of transformed data where the extra precision is not needed.
+pub trait Scalar: Copy + Debug + Display + PartialOrd
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self> {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn abs(self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn is_finite(self) -> bool;
+}
+
+macro_rules! impl_scalar {
+    ($float:ty) => {
+        impl Scalar for $float {
+            fn zero() -> $float { return 0.0; }
+            fn one() -> $float { return 1.0; }
+            fn from_f64(value: f64) -> $float { return value as $float; }
+            fn to_f64(self) -> f64 { return self as f64; }
+            fn sqrt(self) -> $float { return <$float>::sqrt(self); }
+            fn sin(self) -> $float { return <$float>::sin(self); }
+            fn cos(self) -> $float { return <$float>::cos(self); }
+            fn abs(self) -> $float { return <$float>::abs(self); }
+            fn max(self, other: $float) -> $float { return <$float>::max(self, other); }
+            fn is_finite(self) -> bool { return <$float>::is_finite(self); }
+        }
+    };
+}
+
+impl_scalar!(f32);
+impl_scalar!(f64);
 
 // Struct Definitions
 
 #[derive(Debug, Clone, Copy)]
-pub struct Vec3 {
-    pub elems: [f64; 3],
+pub struct Vec3<T = f64> {
+    pub elems: [T; 3],
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct Vec4 {
-    pub elems: [f64; 4],
+pub struct Vec4<T = f64> {
+    pub elems: [T; 4],
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Mat3<T = f64> {
+    pub elems: [T; 9],
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct Mat3 {
-    pub elems: [f64; 9],
+pub struct Mat4<T = f64> {
+    pub elems: [T; 16],
 }
 
+// Unit quaternion stored as <x, y, z, w>
 #[derive(Debug, Clone, Copy)]
-pub struct Mat4 {
-    pub elems: [f64; 16],
+pub struct Quat {
+    pub elems: [f64; 4],
 }
 
 // Method implementations
 // []
 
-impl Index<usize> for Vec3 {
-    type Output = f64;
+impl<T: Scalar> Index<usize> for Vec3<T> {
+    type Output = T;
+
+    fn index(&self, idx: usize) -> &T {
+        return &self.elems[idx];
+    }
+}
 
-    fn index<'a>(&'a self, idx: usize) -> &'a f64 {
+impl<T: Scalar> Index<usize> for Vec4<T> {
+    type Output = T;
+
+    fn index(&self, idx: usize) -> &T {
         return &self.elems[idx];
     }
 }
 
-impl Index<usize> for Vec4 {
+impl Index<usize> for Quat {
     type Output = f64;
 
-    fn index<'a>(&'a self, idx: usize) -> &'a f64 {
+    fn index(&self, idx: usize) -> &f64 {
         return &self.elems[idx];
     }
 }
 
-impl Index<usize> for Mat3 {
-    type Output = [f64];
+impl<T: Scalar> Index<usize> for Mat3<T> {
+    type Output = [T];
 
-    fn index<'a>(&'a self, idx:usize) -> &'a [f64] {
+    fn index(&self, idx:usize) -> &[T] {
         let start_idx = idx*3;
         let end_idx = start_idx + 3;
         return &self.elems[start_idx..end_idx];
     }
 }
 
-impl Index<usize> for Mat4 {
-    type Output = [f64];
+impl<T: Scalar> Index<usize> for Mat4<T> {
+    type Output = [T];
 
-    fn index<'a>(&'a self, idx:usize) -> &'a [f64] {
+    fn index(&self, idx:usize) -> &[T] {
         let start_idx = idx*4;
         let end_idx = start_idx + 4;
         return &self.elems[start_idx..end_idx];
     }
 }
 
+// Display: vectors as <x, y, z>, matrices one row per line
+
+impl<T: Scalar> Display for Vec3<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        return write!(formatter, "<{}, {}, {}>", self[0], self[1], self[2]);
+    }
+}
+
+impl<T: Scalar> Display for Vec4<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        return write!(formatter, "<{}, {}, {}, {}>", self[0], self[1], self[2], self[3]);
+    }
+}
+
+impl Display for Quat {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        return write!(formatter, "<{}, {}, {}, {}>", self[0], self[1], self[2], self[3]);
+    }
+}
+
+fn fmt_rows<T: Scalar>(formatter: &mut fmt::Formatter, elems: &[T], size: usize) -> fmt::Result {
+    for (row_idx, row) in elems.chunks(size).enumerate() {
+        if row_idx > 0 {
+            writeln!(formatter)?;
+        }
+        for (col_idx, elem) in row.iter().enumerate() {
+            if col_idx > 0 {
+                write!(formatter, " ")?;
+            }
+            write!(formatter, "{}", elem)?;
+        }
+    }
+    return Ok(());
+}
+
+impl<T: Scalar> Display for Mat3<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        return fmt_rows(formatter, &self.elems, 3);
+    }
+}
+
+impl<T: Scalar> Display for Mat4<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        return fmt_rows(formatter, &self.elems, 4);
+    }
+}
+
 // +
 
-impl Add for Vec3 {
-    type Output = Vec3;
+impl<T: Scalar> Add for Vec3<T> {
+    type Output = Vec3<T>;
 
-    fn add(self, other: Vec3) -> Vec3 {
+    fn add(self, other: Vec3<T>) -> Vec3<T> {
         return Vec3 {elems: [self.elems[0] + other.elems[0],
                       self.elems[1] + other.elems[1],
                       self.elems[2] + other.elems[2]]};
     }
 }
 
-impl Add for Vec4 {
-    type Output = Vec4;
+impl<T: Scalar> Add for Vec4<T> {
+    type Output = Vec4<T>;
 
-    fn add(self, other: Vec4) -> Vec4 {
+    fn add(self, other: Vec4<T>) -> Vec4<T> {
         return Vec4 {elems: [self.elems[0] + other.elems[0],
                       self.elems[1] + other.elems[1],
                       self.elems[2] + other.elems[2],
@@ -88,10 +184,10 @@ impl Add for Vec4 {
 
 // -
 
-impl Sub for Vec3 {
-    type Output = Vec3;
+impl<T: Scalar> Sub for Vec3<T> {
+    type Output = Vec3<T>;
 
-    fn sub(self, other: Vec3) -> Vec3 {
+    fn sub(self, other: Vec3<T>) -> Vec3<T> {
         return Vec3 {elems: [self.elems[0] - other.elems[0],
                       self.elems[1] - other.elems[1],
                       self.elems[2] - other.elems[2]]};
@@ -99,10 +195,10 @@ impl Sub for Vec3 {
 }
 
 
-impl Sub for Vec4 {
-    type Output = Vec4;
+impl<T: Scalar> Sub for Vec4<T> {
+    type Output = Vec4<T>;
 
-    fn sub(self, other: Vec4) -> Vec4 {
+    fn sub(self, other: Vec4<T>) -> Vec4<T> {
         return Vec4 {elems: [self.elems[0] - other.elems[0],
                              self.elems[1] - other.elems[1],
                              self.elems[2] - other.elems[2],
@@ -112,30 +208,30 @@ impl Sub for Vec4 {
 
 // Scalar Multiplication
 
-impl Mul<f64> for Vec3 {
-    type Output = Vec3;
+impl<T: Scalar> Mul<T> for Vec3<T> {
+    type Output = Vec3<T>;
 
-    fn mul(self, scalar: f64) -> Vec3 {
+    fn mul(self, scalar: T) -> Vec3<T> {
         return Vec3 {elems: [
             scalar*self.elems[0], scalar*self.elems[1], scalar*self.elems[2]
         ]};
     }
 }
 
-impl Mul<f64> for Vec4 {
-    type Output = Vec4;
+impl<T: Scalar> Mul<T> for Vec4<T> {
+    type Output = Vec4<T>;
 
-    fn mul(self, scalar: f64) -> Vec4 {
+    fn mul(self, scalar: T) -> Vec4<T> {
         return Vec4 {elems: [
             scalar*self.elems[0], scalar*self.elems[1], scalar*self.elems[2], scalar*self.elems[3]
         ]};
     }
 }
 
-impl Mul<f64> for Mat3 {
-    type Output = Mat3;
+impl<T: Scalar> Mul<T> for Mat3<T> {
+    type Output = Mat3<T>;
 
-    fn mul(self, scalar: f64) -> Mat3 {
+    fn mul(self, scalar: T) -> Mat3<T> {
         return Mat3 {elems: [
             scalar*self.elems[0], scalar*self.elems[1], scalar*self.elems[2],
             scalar*self.elems[3], scalar*self.elems[4], scalar*self.elems[5],
@@ -144,10 +240,10 @@ impl Mul<f64> for Mat3 {
     }
 }
 
-impl Mul<f64> for Mat4 {
-    type Output = Mat4;
+impl<T: Scalar> Mul<T> for Mat4<T> {
+    type Output = Mat4<T>;
 
-    fn mul(self, scalar: f64) -> Mat4 {
+    fn mul(self, scalar: T) -> Mat4<T> {
         return Mat4 {elems: [
             scalar*self.elems[0], scalar*self.elems[1], scalar*self.elems[2], scalar*self.elems[3],
             scalar*self.elems[4], scalar*self.elems[5], scalar*self.elems[6], scalar*self.elems[7],
@@ -159,30 +255,30 @@ impl Mul<f64> for Mat4 {
 
 // Scalar Division
 
-impl Div<f64> for Vec3 {
-    type Output = Vec3;
+impl<T: Scalar> Div<T> for Vec3<T> {
+    type Output = Vec3<T>;
 
-    fn div(self, scalar: f64)  -> Vec3 {
+    fn div(self, scalar: T)  -> Vec3<T> {
         return Vec3 {elems: [
             self.elems[0]/scalar, self.elems[1]/scalar, self.elems[2]/scalar
         ]};
     }
 }
 
-impl Div<f64> for Vec4 {
-    type Output = Vec4;
+impl<T: Scalar> Div<T> for Vec4<T> {
+    type Output = Vec4<T>;
 
-    fn div(self, scalar: f64)  -> Vec4 {
+    fn div(self, scalar: T)  -> Vec4<T> {
         return Vec4 {elems: [
             self.elems[0]/scalar, self.elems[1]/scalar, self.elems[2]/scalar, self.elems[3]/scalar
         ]};
     }
 }
 
-impl Div<f64> for Mat3 {
-    type Output = Mat3;
+impl<T: Scalar> Div<T> for Mat3<T> {
+    type Output = Mat3<T>;
 
-    fn div(self, scalar: f64)  -> Mat3 {
+    fn div(self, scalar: T)  -> Mat3<T> {
         return Mat3 {elems: [
             self.elems[0]/scalar, self.elems[1]/scalar, self.elems[2]/scalar,
             self.elems[3]/scalar, self.elems[4]/scalar, self.elems[5]/scalar,
@@ -191,10 +287,10 @@ impl Div<f64> for Mat3 {
     }
 }
 
-impl Div<f64> for Mat4 {
-    type Output = Mat4;
+impl<T: Scalar> Div<T> for Mat4<T> {
+    type Output = Mat4<T>;
 
-    fn div(self, scalar: f64)  -> Mat4 {
+    fn div(self, scalar: T)  -> Mat4<T> {
         return Mat4 {elems: [
             self.elems[0]/scalar, self.elems[1]/scalar, self.elems[2]/scalar, self.elems[3]/scalar,
             self.elems[4]/scalar, self.elems[5]/scalar, self.elems[6]/scalar, self.elems[7]/scalar,
@@ -206,10 +302,10 @@ impl Div<f64> for Mat4 {
 
 // Matrix-Vector Multiplication
 
-impl Mul<Vec3> for Mat3 {
-    type Output = Vec3;
+impl<T: Scalar> Mul<Vec3<T>> for Mat3<T> {
+    type Output = Vec3<T>;
 
-    fn mul(self, other: Vec3) -> Vec3 {
+    fn mul(self, other: Vec3<T>) -> Vec3<T> {
         return Vec3 { elems: [
             self[0][0] * other[0] + self[0][1] * other[1] + self[0][2] * other[2],
             self[1][0] * other[0] + self[1][1] * other[1] + self[1][2] * other[2],
@@ -218,10 +314,10 @@ impl Mul<Vec3> for Mat3 {
     }
 }
 
-impl Mul<Vec4> for Mat4 {
-    type Output = Vec4;
+impl<T: Scalar> Mul<Vec4<T>> for Mat4<T> {
+    type Output = Vec4<T>;
 
-    fn mul(self, other: Vec4) -> Vec4 {
+    fn mul(self, other: Vec4<T>) -> Vec4<T> {
         return Vec4 { elems: [
             self[0][0] * other[0] + self[0][1] * other[1] + self[0][2] * other[2] + self[0][3] * other[3],
             self[1][0] * other[0] + self[1][1] * other[1] + self[1][2] * other[2] + self[1][3] * other[3],
@@ -233,11 +329,11 @@ impl Mul<Vec4> for Mat4 {
 
 // Matrix-Matrix Multiplication
 
-impl Mul for Mat3 {
-    type Output = Mat3;
+impl<T: Scalar> Mul for Mat3<T> {
+    type Output = Mat3<T>;
 
-    fn mul(self, other: Mat3) -> Mat3 {
-        let mut init_values = [0.0; 9];
+    fn mul(self, other: Mat3<T>) -> Mat3<T> {
+        let mut init_values = [T::zero(); 9];
         
         for row in 0..3 {
             for col in 0..3 {
@@ -251,11 +347,11 @@ impl Mul for Mat3 {
     }
 }
 
-impl Mul for Mat4 {
-    type Output = Mat4;
+impl<T: Scalar> Mul for Mat4<T> {
+    type Output = Mat4<T>;
 
-    fn mul(self, other: Mat4) -> Mat4 {
-        let mut init_values = [0.0; 16];
+    fn mul(self, other: Mat4<T>) -> Mat4<T> {
+        let mut init_values = [T::zero(); 16];
         
         for row in 0..4 {
             for col in 0..4 {
@@ -270,87 +366,154 @@ impl Mul for Mat4 {
     }
 }
 
+// Quaternion Multiplication (composes rotations, `self` applied last)
+
+impl Mul for Quat {
+    type Output = Quat;
+
+    fn mul(self, other: Quat) -> Quat {
+        let [x1, y1, z1, w1] = self.elems;
+        let [x2, y2, z2, w2] = other.elems;
+
+        return Quat {elems: [
+            w1*x2 + x1*w2 + y1*z2 - z1*y2,
+            w1*y2 - x1*z2 + y1*w2 + z1*x2,
+            w1*z2 + x1*y2 - y1*x2 + z1*w2,
+            w1*w2 - x1*x2 - y1*y2 - z1*z2,
+        ]};
+    }
+}
+
 // Other required functions
 
-impl Vec3 {
-    pub fn new(x: f64, y: f64, z: f64) -> Vec3 {
+impl<T: Scalar> Vec3<T> {
+    pub fn new(x: T, y: T, z: T) -> Vec3<T> {
         return Vec3{elems: [x, y, z]};
     }
-    pub fn default() -> Vec3 {
-        return Vec3{elems: [0.0, 0.0, 0.0]};
+    pub fn default() -> Vec3<T> {
+        return Vec3{elems: [T::zero(); 3]};
     }
-    pub fn log(self) -> () {
-        println!("<{}, {}, {}>", self[0], self[1], self[2]);
+    pub fn cast<U: Scalar>(self) -> Vec3<U> {
+        return Vec3 {elems: self.elems.map(|elem| U::from_f64(elem.to_f64()))};
     }
-    pub fn dot(self, other: Vec3) -> f64 {
+    pub fn dot(self, other: Vec3<T>) -> T {
         return self[0]*other[0] + self[1]*other[1] + self[2]*other[2];
     }
-    pub fn cross(self, other: Vec3) -> Vec3 {
+    pub fn cross(self, other: Vec3<T>) -> Vec3<T> {
         return Vec3{elems: [
             self[1] * other[2] - self[2] * other[1],
             self[2] * other[0] - self[0] * other[2],
             self[0] * other[1] - self[1] * other[0]
         ]};
     }
+    pub fn length(self) -> T {
+        return self.dot(self).sqrt();
+    }
+    pub fn normalize(self) -> Vec3<T> {
+        let length = self.length();
+
+        if length == T::zero() {
+            return self;
+        }
+        return self / length;
+    }
 }
 
-impl Vec4 {
-    pub fn new(x: f64, y: f64, z: f64, w: f64) -> Vec4 {
+impl<T: Scalar> Vec4<T> {
+    pub fn new(x: T, y: T, z: T, w: T) -> Vec4<T> {
         return Vec4 {elems: [x, y, z, w]};
     }
-    pub fn default() -> Vec4 {
-        return Vec4 {elems: [0.0, 0.0, 0.0, 1.0]};
+    pub fn default() -> Vec4<T> {
+        return Vec4 {elems: [T::zero(), T::zero(), T::zero(), T::one()]};
     }
-    pub fn log(self) -> () {
-        println!("<{}, {}, {}, {}>", self[0], self[1], self[2], self[3]);
+    pub fn cast<U: Scalar>(self) -> Vec4<U> {
+        return Vec4 {elems: self.elems.map(|elem| U::from_f64(elem.to_f64()))};
     }
-    fn dot(self, other: Vec4) -> f64 {
+    pub fn dot(self, other: Vec4<T>) -> T {
         return self[0] * other[0] + self[1] * other[1] + self[2] * other[2] + self[3] * other[3];
     }
 }
 
-impl Mat3 {
-    pub fn new(arr: [f64;9]) -> Mat3 {
+impl<T: Scalar> Mat3<T> {
+    pub fn new(arr: [T;9]) -> Mat3<T> {
         return Mat3 {elems: arr};
     }
 
-    pub fn default() -> Mat3 {
+    pub fn default() -> Mat3<T> {
+        return Mat3{elems: [T::zero(); 9]};
+    }
+
+    pub fn identity() -> Mat3<T> {
+        return scale_matrix3(T::one(), T::one(), T::one());
+    }
+    pub fn cast<U: Scalar>(self) -> Mat3<U> {
+        return Mat3 {elems: self.elems.map(|elem| U::from_f64(elem.to_f64()))};
+    }
+    pub fn transpose(self) -> Mat3<T> {
+        let m = self.elems;
         return Mat3{elems: [
-            0.0, 0.0, 0.0, 
-            0.0, 0.0, 0.0, 
-            0.0, 0.0, 0.0, 
-        ]}
+            m[0], m[3], m[6],
+            m[1], m[4], m[7],
+            m[2], m[5], m[8],
+        ]};
+    }
+    pub fn determinant(self) -> T {
+        let m = self.elems;
+        return m[0] * (m[4] * m[8] - m[5] * m[7]) -
+               m[1] * (m[3] * m[8] - m[5] * m[6]) +
+               m[2] * (m[3] * m[7] - m[4] * m[6]);
+    }
+    fn adjugate(self) -> Mat3<T> {
+        let m = self.elems;
+        return Mat3{elems: [
+            m[4] * m[8] - m[5] * m[7], m[2] * m[7] - m[1] * m[8], m[1] * m[5] - m[2] * m[4],
+            m[5] * m[6] - m[3] * m[8], m[0] * m[8] - m[2] * m[6], m[2] * m[3] - m[0] * m[5],
+            m[3] * m[7] - m[4] * m[6], m[1] * m[6] - m[0] * m[7], m[0] * m[4] - m[1] * m[3],
+        ]};
+    }
+    // Adjugate over determinant; like `Mat4::inverse` it does not check for singular matrices
+    pub fn inverse(self) -> Mat3<T> {
+        return self.adjugate() / self.determinant();
     }
+    // None when the determinant is within `epsilon` of zero
+    pub fn try_inverse(self, epsilon: T) -> Option<Mat3<T>> {
+        let det = self.determinant();
 
-    pub fn log(self) -> () {
-        for row in 0..3 {
-            for col in 0..3 {
-                print!("{} ", self[row][col]);
-                io::stdout().flush().unwrap();
-            }
-            println!();
+        if !det.is_finite() || det.abs() <= epsilon {
+            return None;
         }
+        return Some(self.adjugate() / det);
+    }
+    // Whether the columns are unit length and perpendicular, within rounding of a few products
+    pub fn is_orthonormal(self) -> bool {
+        let product = self.transpose() * self;
+        let identity = Mat3::<T>::identity();
+
+        return (0..9).all(|idx| (product.elems[idx] - identity.elems[idx]).to_f64().abs() < 1e-5);
+    }
+    // Embeds the matrix as the linear part of an affine transform that then moves by `translation`
+    pub fn to_mat4(self, translation: Vec3<T>) -> Mat4<T> {
+        let m = self.elems;
+        return Mat4{elems: [
+            m[0], m[1], m[2], translation[0],
+            m[3], m[4], m[5], translation[1],
+            m[6], m[7], m[8], translation[2],
+            T::zero(), T::zero(), T::zero(), T::one(),
+        ]};
     }
 }
 
-impl Mat4 {
-    pub fn new(arr: [f64; 16]) -> Mat4 {
+impl<T: Scalar> Mat4<T> {
+    pub fn new(arr: [T; 16]) -> Mat4<T> {
         return Mat4{elems: arr};
     }
-    pub fn default() -> Mat4 {
-        return Mat4{elems: [
-            0.0, 0.0, 0.0, 0.0,
-            0.0, 0.0, 0.0, 0.0,
-            0.0, 0.0, 0.0, 0.0,
-            0.0, 0.0, 0.0, 0.0,
-        ]}
+    pub fn default() -> Mat4<T> {
+        return Mat4{elems: [T::zero(); 16]};
     }
-    pub fn inverse(self) -> Mat4 {
-        let mut inv = [0.0; 16];
-        let mut m = [0.0; 16];
-        let mut det;
-        
-        m = self.elems;        
+    // Transposed cofactor matrix and determinant, shared by `inverse` and `try_inverse`
+    fn adjugate(self) -> (Mat4<T>, T) {
+        let mut inv = [T::zero(); 16];
+        let m = self.elems;
 
         inv[0] = m[5]  * m[10] * m[15] -
                 m[5]  * m[11] * m[14] -
@@ -464,66 +627,319 @@ impl Mat4 {
                 m[8] * m[1] * m[6] -
                 m[8] * m[2] * m[5];
 
-        det = m[0] * inv[0] + m[1] * inv[4] + m[2] * inv[8] + m[3] * inv[12];
+        let det = m[0] * inv[0] + m[1] * inv[4] + m[2] * inv[8] + m[3] * inv[12];
 
-        det = 1.0 / det;
-        let temp = Mat4{elems: inv};
-        let inverted_matrix = temp * det;
+        return (Mat4{elems: inv}, det);
+    }
+    pub fn determinant(self) -> T {
+        return self.adjugate().1;
+    }
+    // Full inverse without a singularity check; a singular matrix gives infinities and NaNs.
+    // Prefer `try_inverse` unless the matrix is known to be invertible.
+    pub fn inverse(self) -> Mat4<T> {
+        let (adjugate, det) = self.adjugate();
+        return adjugate * (T::one() / det);
+    }
+    // None when the determinant is within `epsilon` of zero. The determinant scales with the
+    // cube of the matrix's scale, so `epsilon` should be chosen for the scales in use.
+    pub fn try_inverse(self, epsilon: T) -> Option<Mat4<T>> {
+        let (adjugate, det) = self.adjugate();
 
-        return inverted_matrix;
+        if !det.is_finite() || det.abs() <= epsilon {
+            return None;
+        }
+        return Some(adjugate * (T::one() / det));
     }
-    pub fn log(self) -> () {
-        for row in 0..4 {
-            for col in 0..4 {
-                print!("{} ", self[row][col]);
-                io::stdout().flush().unwrap();
-            }
-            println!();
+    pub fn is_affine(self) -> bool {
+        let (zero, one) = (T::zero(), T::one());
+        return self[3][0] == zero && self[3][1] == zero && self[3][2] == zero && self[3][3] == one;
+    }
+    // Inverse of an affine transform from its 3x3 part alone: (L, t) becomes (L^-1, -L^-1 t).
+    // None for a projective matrix or a singular linear part.
+    pub fn try_affine_inverse(self, epsilon: T) -> Option<Mat4<T>> {
+        if !self.is_affine() {
+            return None;
+        }
+
+        let linear = self.upper_left().try_inverse(epsilon)?;
+        return Some(linear.to_mat4(linear * self.translation() * -T::one()));
+    }
+    // Inverse of a rotation followed by a translation, where the 3x3 part is orthonormal and its
+    // inverse is its transpose. Other matrices give wrong results, which debug builds catch.
+    pub fn rigid_inverse(self) -> Mat4<T> {
+        debug_assert!(self.upper_left().is_orthonormal(), "rigid_inverse of a matrix that is not a rotation:\n{}", self);
+
+        let rotation = self.upper_left().transpose();
+        return rotation.to_mat4(rotation * self.translation() * -T::one());
+    }
+    // The rotation and scale part of an affine transform
+    pub fn upper_left(self) -> Mat3<T> {
+        let m = self.elems;
+        return Mat3{elems: [
+            m[0], m[1], m[2],
+            m[4], m[5], m[6],
+            m[8], m[9], m[10],
+        ]};
+    }
+    pub fn translation(self) -> Vec3<T> {
+        return Vec3::new(self[0][3], self[1][3], self[2][3]);
+    }
+    // Transforms surface normals so they stay perpendicular under non-uniform scale. None when a
+    // scale axis is (within `epsilon` of) zero, since the flattened surface has no normals.
+    pub fn normal_matrix(self, epsilon: T) -> Option<Mat3<T>> {
+        return self.upper_left().try_inverse(epsilon).map(|inverse| inverse.transpose());
+    }
+    pub fn transform_point(self, point: Vec3<T>) -> Vec3<T> {
+        let transformed = self * Vec4::new(point[0], point[1], point[2], T::one());
+        return Vec3::new(transformed[0], transformed[1], transformed[2]);
+    }
+    pub fn transform_direction(self, direction: Vec3<T>) -> Vec3<T> {
+        return self.upper_left() * direction;
+    }
+    // Length of the longest basis vector in the upper 3x3, i.e. the largest scale factor applied
+    pub fn max_axis_scale(self) -> T {
+        let mut max_squared = T::zero();
+
+        for col in 0..3 {
+            let length_squared = self[0][col]*self[0][col] + self[1][col]*self[1][col] + self[2][col]*self[2][col];
+            max_squared = max_squared.max(length_squared);
         }
+
+        return max_squared.sqrt();
+    }
+    pub fn identity() -> Mat4<T> {
+        return Mat3::identity().to_mat4(Vec3::default());
+    }
+    pub fn cast<U: Scalar>(self) -> Mat4<U> {
+        return Mat4 {elems: self.elems.map(|elem| U::from_f64(elem.to_f64()))};
+    }
+}
+
+impl Quat {
+    pub fn new(x: f64, y: f64, z: f64, w: f64) -> Quat {
+        return Quat {elems: [x, y, z, w]};
+    }
+    pub fn default() -> Quat {
+        return Quat {elems: [0.0, 0.0, 0.0, 1.0]};
+    }
+    pub fn from_axis_angle(axis: Vec3, theta: f64) -> Quat {
+        let n = axis.normalize();
+        let s = (theta / 2.0).sin();
+        return Quat {elems: [n[0] * s, n[1] * s, n[2] * s, (theta / 2.0).cos()]};
+    }
+    // Reads the rotation from the upper 3x3 of `matrix` (Shepperd's method)
+    pub fn from_rotation_matrix(matrix: Mat4) -> Quat {
+        let m = |row: usize, col: usize| matrix[row][col];
+        let trace = m(0, 0) + m(1, 1) + m(2, 2);
+
+        let quat = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Quat::new((m(2, 1) - m(1, 2)) / s, (m(0, 2) - m(2, 0)) / s, (m(1, 0) - m(0, 1)) / s, 0.25 * s)
+        } else if m(0, 0) > m(1, 1) && m(0, 0) > m(2, 2) {
+            let s = (1.0 + m(0, 0) - m(1, 1) - m(2, 2)).sqrt() * 2.0;
+            Quat::new(0.25 * s, (m(0, 1) + m(1, 0)) / s, (m(0, 2) + m(2, 0)) / s, (m(2, 1) - m(1, 2)) / s)
+        } else if m(1, 1) > m(2, 2) {
+            let s = (1.0 + m(1, 1) - m(0, 0) - m(2, 2)).sqrt() * 2.0;
+            Quat::new((m(0, 1) + m(1, 0)) / s, 0.25 * s, (m(1, 2) + m(2, 1)) / s, (m(0, 2) - m(2, 0)) / s)
+        } else {
+            let s = (1.0 + m(2, 2) - m(0, 0) - m(1, 1)).sqrt() * 2.0;
+            Quat::new((m(0, 2) + m(2, 0)) / s, (m(1, 2) + m(2, 1)) / s, 0.25 * s, (m(1, 0) - m(0, 1)) / s)
+        };
+
+        return quat.normalize();
+    }
+    // Same convention as `add_shape`: rotate about x, then y, then z
+    pub fn from_euler_angles(x_r: f64, y_r: f64, z_r: f64) -> Quat {
+        let x = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), x_r);
+        let y = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), y_r);
+        let z = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), z_r);
+        return z * y * x;
+    }
+    pub fn dot(self, other: Quat) -> f64 {
+        return self[0] * other[0] + self[1] * other[1] + self[2] * other[2] + self[3] * other[3];
+    }
+    pub fn normalize(self) -> Quat {
+        let length = self.dot(self).sqrt();
+
+        if length == 0.0 {
+            return Quat::default();
+        }
+        return Quat {elems: [self[0] / length, self[1] / length, self[2] / length, self[3] / length]};
+    }
+    // Shortest rotation taking the direction of `from` onto the direction of `to`
+    pub fn rotation_between(from: Vec3, to: Vec3) -> Quat {
+        let (from, to) = (from.normalize(), to.normalize());
+        let cos_theta = from.dot(to).clamp(-1.0, 1.0);
+
+        if cos_theta > 1.0 - 1e-12 {
+            return Quat::default();
+        }
+        if cos_theta < -1.0 + 1e-12 {
+            // Opposite directions: turn half way around any axis perpendicular to `from`
+            let helper = if from[0].abs() < 0.9 { Vec3::new(1.0, 0.0, 0.0) } else { Vec3::new(0.0, 1.0, 0.0) };
+            return Quat::from_axis_angle(from.cross(helper), std::f64::consts::PI);
+        }
+
+        return Quat::from_axis_angle(from.cross(to), cos_theta.acos());
+    }
+    pub fn conjugate(self) -> Quat {
+        return Quat {elems: [-self[0], -self[1], -self[2], self[3]]};
+    }
+    pub fn rotate(self, vector: Vec3) -> Vec3 {
+        let rotated = self * Quat::new(vector[0], vector[1], vector[2], 0.0) * self.conjugate();
+        return Vec3::new(rotated[0], rotated[1], rotated[2]);
+    }
+    pub fn to_matrix(self) -> Mat4 {
+        let [x, y, z, w] = self.normalize().elems;
+
+        return Mat4{elems: [
+            1.0 - 2.0*(y*y + z*z), 2.0*(x*y - z*w),       2.0*(x*z + y*w),       0.0,
+            2.0*(x*y + z*w),       1.0 - 2.0*(x*x + z*z), 2.0*(y*z - x*w),       0.0,
+            2.0*(x*z - y*w),       2.0*(y*z + x*w),       1.0 - 2.0*(x*x + y*y), 0.0,
+            0.0,                   0.0,                   0.0,                   1.0
+        ]};
+    }
+    // Spherical interpolation along the shorter arc, falling back to nlerp for nearly equal inputs
+    pub fn slerp(self, other: Quat, t: f64) -> Quat {
+        let mut end = other;
+        let mut cos_theta = self.dot(other);
+
+        if cos_theta < 0.0 {
+            end = Quat {elems: [-other[0], -other[1], -other[2], -other[3]]};
+            cos_theta = -cos_theta;
+        }
+
+        let (scale_start, scale_end) = if cos_theta > 0.9995 {
+            (1.0 - t, t)
+        } else {
+            let theta = cos_theta.acos();
+            let sin_theta = theta.sin();
+            (((1.0 - t) * theta).sin() / sin_theta, (t * theta).sin() / sin_theta)
+        };
+
+        return Quat {elems: [
+            scale_start * self[0] + scale_end * end[0],
+            scale_start * self[1] + scale_end * end[1],
+            scale_start * self[2] + scale_end * end[2],
+            scale_start * self[3] + scale_end * end[3],
+        ]}.normalize();
     }
 }
 
 // Other important functions and constants
 
-#[allow(dead_code)]
-pub fn x_rotation_matrix(theta: f64) -> Mat4 {
-    return Mat4{elems: [
-        1.0, 0.0,          0.0,           0.0,
-        0.0, theta.cos(), -(theta.sin()), 0.0,
-        0.0, theta.sin(),  theta.cos(),   0.0,
-        0.0, 0.0,           0.0,          1.0
+pub fn x_rotation_matrix3<T: Scalar>(theta: T) -> Mat3<T> {
+    let (zero, one) = (T::zero(), T::one());
+    return Mat3{elems: [
+        one,  zero,         zero,
+        zero, theta.cos(), -(theta.sin()),
+        zero, theta.sin(),  theta.cos(),
     ]};
 }
 
-#[allow(dead_code)]
-pub fn y_rotation_matrix(theta: f64) -> Mat4 {
-    return Mat4{elems: [
-         theta.cos(),   0.0, theta.sin(), 0.0,
-         0.0,           1.0, 0.0,         0.0,
-        -theta.sin(), 0.0, theta.cos(), 0.0,
-         0.0,           0.0, 0.0,         1.0
+pub fn y_rotation_matrix3<T: Scalar>(theta: T) -> Mat3<T> {
+    let (zero, one) = (T::zero(), T::one());
+    return Mat3{elems: [
+         theta.cos(), zero, theta.sin(),
+         zero,        one,  zero,
+        -theta.sin(), zero, theta.cos(),
     ]};
 }
 
-#[allow(dead_code)]
-pub fn z_rotation_matrix(theta: f64) -> Mat4 {
-    return Mat4{elems: [
-        theta.cos(), -(theta.sin()), 0.0, 0.0,
-        theta.sin(),  theta.cos(),   0.0, 0.0, 
-        0.0,          0.0,           1.0, 0.0, 
-        0.0,          0.0,           0.0, 1.0
-    ]}
+pub fn z_rotation_matrix3<T: Scalar>(theta: T) -> Mat3<T> {
+    let (zero, one) = (T::zero(), T::one());
+    return Mat3{elems: [
+        theta.cos(), -(theta.sin()), zero,
+        theta.sin(),  theta.cos(),   zero,
+        zero,         zero,          one,
+    ]};
+}
+
+// Rodrigues' formula; `axis` does not need to be normalized
+pub fn axis_angle_rotation_matrix3<T: Scalar>(axis: Vec3<T>, theta: T) -> Mat3<T> {
+    let n = axis.normalize();
+    let (x, y, z) = (n[0], n[1], n[2]);
+    let (s, c) = (theta.sin(), theta.cos());
+    let t = T::one() - c;
+
+    return Mat3{elems: [
+        t*x*x + c,   t*x*y - s*z, t*x*z + s*y,
+        t*x*y + s*z, t*y*y + c,   t*y*z - s*x,
+        t*x*z - s*y, t*y*z + s*x, t*z*z + c,
+    ]};
 }
 
-pub fn translation_matrix(delta_x: f64, delta_y: f64, delta_z: f64) -> Mat4 {
-    return Mat4 {elems: [
-        1.0, 0.0, 0.0, delta_x,
-        0.0, 1.0, 0.0, delta_y,
-        0.0, 0.0, 1.0, delta_z,
-        0.0, 0.0, 0.0,     1.0
+pub fn scale_matrix3<T: Scalar>(scale_x: T, scale_y: T, scale_z: T) -> Mat3<T> {
+    let zero = T::zero();
+    return Mat3 {elems: [
+        scale_x, zero,    zero,
+        zero,    scale_y, zero,
+        zero,    zero,    scale_z,
     ]}
 }
 
+#[allow(dead_code)]
+pub fn x_rotation_matrix<T: Scalar>(theta: T) -> Mat4<T> {
+    return x_rotation_matrix3(theta).to_mat4(Vec3::default());
+}
+
+#[allow(dead_code)]
+pub fn y_rotation_matrix<T: Scalar>(theta: T) -> Mat4<T> {
+    return y_rotation_matrix3(theta).to_mat4(Vec3::default());
+}
+
+#[allow(dead_code)]
+pub fn z_rotation_matrix<T: Scalar>(theta: T) -> Mat4<T> {
+    return z_rotation_matrix3(theta).to_mat4(Vec3::default());
+}
+
+pub fn axis_angle_rotation_matrix<T: Scalar>(axis: Vec3<T>, theta: T) -> Mat4<T> {
+    return axis_angle_rotation_matrix3(axis, theta).to_mat4(Vec3::default());
+}
+
+// Inverse of `axis_angle_rotation_matrix`, returned as the rotation vector axis * angle
+pub fn rotation_vector(rotation: Mat4) -> Vec3 {
+    let trace = rotation[0][0] + rotation[1][1] + rotation[2][2];
+    let theta = ((trace - 1.0) / 2.0).clamp(-1.0, 1.0).acos();
+
+    if theta.abs() < 1e-9 {
+        return Vec3::default();
+    }
+
+    let axis = Vec3::new(
+        rotation[2][1] - rotation[1][2],
+        rotation[0][2] - rotation[2][0],
+        rotation[1][0] - rotation[0][1],
+    );
+
+    if axis.length() < 1e-9 {
+        // theta is pi: the axis is the column of (R + I) / 2 with the largest diagonal entry
+        let mut col = 0;
+        for idx in 1..3 {
+            if rotation[idx][idx] > rotation[col][col] {
+                col = idx;
+            }
+        }
+        let column = Vec3::new(rotation[0][col], rotation[1][col], rotation[2][col]);
+        let mut unit = Vec3::default();
+        unit.elems[col] = 1.0;
+        return (column + unit).normalize() * theta;
+    }
+
+    return axis.normalize() * theta;
+}
+
+pub fn scale_matrix<T: Scalar>(scale_x: T, scale_y: T, scale_z: T) -> Mat4<T> {
+    return scale_matrix3(scale_x, scale_y, scale_z).to_mat4(Vec3::default());
+}
+
+pub fn translation_matrix<T: Scalar>(delta_x: T, delta_y: T, delta_z: T) -> Mat4<T> {
+    return Mat3::identity().to_mat4(Vec3::new(delta_x, delta_y, delta_z));
+}
+
+// Default tolerance on the determinant when checking whether a matrix can be inverted
+pub const INVERSE_EPSILON: f64 = 1e-12;
+
 #[allow(dead_code)]
 pub const IDENTITY3X3: Mat3 = Mat3 {elems: [
     1.0, 0.0, 0.0,
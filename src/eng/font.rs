@@ -0,0 +1,244 @@
+use std::string::String;
+
+use super::euler::{Vec3, IDENTITY4X4};
+use super::renderer::GameObject;
+
+// A stroke font in the style of the 80s arcade vector fonts. Glyphs are polylines on a grid
+// 4 units wide and 6 units tall with the baseline at y = 0 and +y up.
+type Glyph = &'static [&'static [(i8, i8)]];
+
+pub const GLYPH_WIDTH: f64 = 4.0;
+pub const GLYPH_HEIGHT: f64 = 6.0;
+pub const GLYPH_ADVANCE: f64 = 6.0;
+pub const LINE_ADVANCE: f64 = 9.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HorizontalAlign {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VerticalAlign {
+    Top,
+    Middle,
+    Bottom,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TextStyle {
+    pub size: f64,                       // Height of a capital letter
+    pub horizontal_align: HorizontalAlign,
+    pub vertical_align: VerticalAlign,
+}
+
+impl HorizontalAlign {
+    pub fn parse(name: &str) -> Option<HorizontalAlign> {
+        return match name {
+            "left" => Some(HorizontalAlign::Left),
+            "center" => Some(HorizontalAlign::Center),
+            "right" => Some(HorizontalAlign::Right),
+            _ => None,
+        };
+    }
+}
+
+impl VerticalAlign {
+    pub fn parse(name: &str) -> Option<VerticalAlign> {
+        return match name {
+            "top" => Some(VerticalAlign::Top),
+            "middle" => Some(VerticalAlign::Middle),
+            "bottom" => Some(VerticalAlign::Bottom),
+            _ => None,
+        };
+    }
+}
+
+impl TextStyle {
+    pub fn new(size: f64, horizontal_align: HorizontalAlign, vertical_align: VerticalAlign) -> TextStyle {
+        return TextStyle {size: size, horizontal_align: horizontal_align, vertical_align: vertical_align};
+    }
+
+    pub fn default() -> TextStyle {
+        return TextStyle::new(1.0, HorizontalAlign::Left, VerticalAlign::Bottom);
+    }
+}
+
+// Lowercase letters are drawn as capitals and anything without a glyph as '?'
+pub fn glyph(character: char) -> Glyph {
+    return match character.to_ascii_uppercase() {
+        'A' => &[&[(0, 0), (0, 4), (2, 6), (4, 4), (4, 0)], &[(0, 3), (4, 3)]],
+        'B' => &[&[(0, 0), (0, 6), (3, 6), (4, 5), (4, 4), (3, 3), (0, 3)], &[(3, 3), (4, 2), (4, 1), (3, 0), (0, 0)]],
+        'C' => &[&[(4, 0), (0, 0), (0, 6), (4, 6)]],
+        'D' => &[&[(0, 0), (0, 6), (2, 6), (4, 4), (4, 2), (2, 0), (0, 0)]],
+        'E' => &[&[(4, 0), (0, 0), (0, 6), (4, 6)], &[(0, 3), (3, 3)]],
+        'F' => &[&[(0, 0), (0, 6), (4, 6)], &[(0, 3), (3, 3)]],
+        'G' => &[&[(2, 2), (4, 2), (4, 0), (0, 0), (0, 6), (4, 6), (4, 4)]],
+        'H' => &[&[(0, 0), (0, 6)], &[(4, 0), (4, 6)], &[(0, 3), (4, 3)]],
+        'I' => &[&[(0, 0), (4, 0)], &[(2, 0), (2, 6)], &[(0, 6), (4, 6)]],
+        'J' => &[&[(0, 2), (2, 0), (4, 0), (4, 6)]],
+        'K' => &[&[(0, 0), (0, 6)], &[(4, 6), (0, 3), (4, 0)]],
+        'L' => &[&[(0, 6), (0, 0), (4, 0)]],
+        'M' => &[&[(0, 0), (0, 6), (2, 4), (4, 6), (4, 0)]],
+        'N' => &[&[(0, 0), (0, 6), (4, 0), (4, 6)]],
+        'O' => &[&[(0, 0), (0, 6), (4, 6), (4, 0), (0, 0)]],
+        'P' => &[&[(0, 0), (0, 6), (4, 6), (4, 3), (0, 3)]],
+        'Q' => &[&[(0, 0), (0, 6), (4, 6), (4, 2), (2, 0), (0, 0)], &[(2, 2), (4, 0)]],
+        'R' => &[&[(0, 0), (0, 6), (4, 6), (4, 3), (0, 3)], &[(1, 3), (4, 0)]],
+        'S' => &[&[(0, 0), (4, 0), (4, 3), (0, 3), (0, 6), (4, 6)]],
+        'T' => &[&[(0, 6), (4, 6)], &[(2, 6), (2, 0)]],
+        'U' => &[&[(0, 6), (0, 0), (4, 0), (4, 6)]],
+        'V' => &[&[(0, 6), (2, 0), (4, 6)]],
+        'W' => &[&[(0, 6), (0, 0), (2, 2), (4, 0), (4, 6)]],
+        'X' => &[&[(0, 0), (4, 6)], &[(0, 6), (4, 0)]],
+        'Y' => &[&[(0, 6), (2, 4), (4, 6)], &[(2, 4), (2, 0)]],
+        'Z' => &[&[(0, 6), (4, 6), (0, 0), (4, 0)]],
+        '0' => &[&[(0, 0), (0, 6), (4, 6), (4, 0), (0, 0), (4, 6)]],
+        '1' => &[&[(1, 5), (2, 6), (2, 0)], &[(1, 0), (3, 0)]],
+        '2' => &[&[(0, 6), (4, 6), (4, 3), (0, 3), (0, 0), (4, 0)]],
+        '3' => &[&[(0, 6), (4, 6), (4, 0), (0, 0)], &[(0, 3), (4, 3)]],
+        '4' => &[&[(0, 6), (0, 3), (4, 3)], &[(4, 6), (4, 0)]],
+        '5' => &[&[(4, 6), (0, 6), (0, 3), (4, 3), (4, 0), (0, 0)]],
+        '6' => &[&[(0, 6), (0, 0), (4, 0), (4, 3), (0, 3)]],
+        '7' => &[&[(0, 6), (4, 6), (4, 0)]],
+        '8' => &[&[(0, 0), (0, 6), (4, 6), (4, 0), (0, 0)], &[(0, 3), (4, 3)]],
+        '9' => &[&[(4, 3), (0, 3), (0, 6), (4, 6), (4, 0)]],
+        ' ' => &[],
+        '.' => &[&[(2, 0), (2, 1)]],
+        ',' => &[&[(2, 1), (1, -1)]],
+        '!' => &[&[(2, 6), (2, 2)], &[(2, 0), (2, 1)]],
+        '?' => &[&[(0, 5), (1, 6), (3, 6), (4, 5), (4, 4), (2, 3), (2, 2)], &[(2, 0), (2, 1)]],
+        '-' => &[&[(1, 3), (3, 3)]],
+        '+' => &[&[(1, 3), (3, 3)], &[(2, 2), (2, 4)]],
+        '=' => &[&[(0, 2), (4, 2)], &[(0, 4), (4, 4)]],
+        '*' => &[&[(0, 3), (4, 3)], &[(1, 1), (3, 5)], &[(1, 5), (3, 1)]],
+        ':' => &[&[(2, 1), (2, 2)], &[(2, 4), (2, 5)]],
+        '/' => &[&[(0, 0), (4, 6)]],
+        '\'' => &[&[(2, 6), (2, 4)]],
+        '"' => &[&[(1, 6), (1, 4)], &[(3, 6), (3, 4)]],
+        '(' => &[&[(3, 6), (1, 4), (1, 2), (3, 0)]],
+        ')' => &[&[(1, 6), (3, 4), (3, 2), (1, 0)]],
+        '<' => &[&[(4, 6), (0, 3), (4, 0)]],
+        '>' => &[&[(0, 6), (4, 3), (0, 0)]],
+        '_' => &[&[(0, 0), (4, 0)]],
+        _ => glyph('?'),
+    };
+}
+
+fn line_width(line: &str) -> f64 {
+    let characters = line.chars().count();
+
+    if characters == 0 {
+        return 0.0;
+    }
+    return (characters - 1) as f64 * GLYPH_ADVANCE + GLYPH_WIDTH;
+}
+
+// Size of the text block in font units
+pub fn measure(text: &str) -> (f64, f64) {
+    let lines = text.split('\n').count();
+    let width = text.split('\n').map(line_width).fold(0.0, f64::max);
+    let height = (lines - 1) as f64 * LINE_ADVANCE + GLYPH_HEIGHT;
+
+    return (width, height);
+}
+
+// Lays out the text as polylines in a space where the anchor sits at the origin and a capital
+// letter is `style.size` tall. Each line of the text is aligned on its own.
+pub fn layout(text: &str, style: &TextStyle) -> Vec::<Vec::<(f64, f64)>> {
+    let scale = style.size / GLYPH_HEIGHT;
+    let (_, block_height) = measure(text);
+
+    let top = match style.vertical_align {
+        VerticalAlign::Top => 0.0,
+        VerticalAlign::Middle => block_height / 2.0,
+        VerticalAlign::Bottom => block_height,
+    };
+
+    let mut polylines: Vec::<Vec::<(f64, f64)>> = Vec::new();
+
+    for (line_idx, line) in text.split('\n').enumerate() {
+        let left = match style.horizontal_align {
+            HorizontalAlign::Left => 0.0,
+            HorizontalAlign::Center => -line_width(line) / 2.0,
+            HorizontalAlign::Right => -line_width(line),
+        };
+        let baseline = top - GLYPH_HEIGHT - line_idx as f64 * LINE_ADVANCE;
+
+        for (char_idx, character) in line.chars().enumerate() {
+            let origin_x = left + char_idx as f64 * GLYPH_ADVANCE;
+
+            for stroke in glyph(character).iter() {
+                polylines.push(stroke.iter()
+                    .map(|&(x, y)| ((origin_x + x as f64) * scale, (baseline + y as f64) * scale))
+                    .collect());
+            }
+        }
+    }
+
+    return polylines;
+}
+
+// Flat tail_x, tail_y, head_x, head_y segments in the same layout as `render_frame`'s output.
+// `aspect` scales x the same way the projection does so glyphs keep their shape on screen.
+pub fn text_lines(text: &str, x: f64, y: f64, aspect: f64, style: &TextStyle) -> Vec::<f64> {
+    let mut lines: Vec::<f64> = Vec::<f64>::new();
+
+    for polyline in layout(text, style) {
+        for pair in polyline.windows(2) {
+            lines.push(x + pair[0].0 * aspect);
+            lines.push(y + pair[0].1);
+            lines.push(x + pair[1].0 * aspect);
+            lines.push(y + pair[1].1);
+        }
+    }
+
+    return lines;
+}
+
+// The text as a flat object in its xy-plane, aligned around the object's origin, so it can be
+// placed, spun and animated like any other shape
+pub fn make_text(text: &str, style: &TextStyle) -> GameObject {
+    let mut points: Vec::<Vec3> = Vec::new();
+    let mut connections: Vec::<usize> = Vec::new();
+
+    for polyline in layout(text, style) {
+        let first = points.len();
+
+        for &(x, y) in &polyline {
+            points.push(Vec3::new(x, y, 0.0));
+        }
+        for idx in first..points.len().saturating_sub(1) {
+            connections.push(idx);
+            connections.push(idx + 1);
+        }
+    }
+
+    return GameObject::new(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 0.0),
+        IDENTITY4X4,
+        IDENTITY4X4,
+        points,
+        connections);
+}
+
+// Text drawn over the 3D scene, anchored in the [-1, 1] space of the line buffer
+#[derive(Clone, Debug)]
+pub struct OverlayText {
+    pub text: String,
+    pub x: f64,
+    pub y: f64,
+    pub style: TextStyle,
+}
+
+impl OverlayText {
+    pub fn new(text: &str, x: f64, y: f64, style: TextStyle) -> OverlayText {
+        return OverlayText {text: String::from(text), x: x, y: y, style: style};
+    }
+
+    pub fn lines(&self, aspect: f64) -> Vec::<f64> {
+        return text_lines(&self.text, self.x, self.y, aspect, &self.style);
+    }
+}
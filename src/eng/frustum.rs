@@ -0,0 +1,65 @@
+use super::euler::{Vec3, Vec4, Mat4};
+use super::bounds::{BoundingSphere, Aabb};
+
+// Planes are stored as <a, b, c, d> with a unit normal pointing into the frustum,
+// so a point p is inside a plane when a*x + b*y + c*z + d >= 0.
+#[derive(Clone, Copy, Debug)]
+pub struct Frustum {
+    pub planes: [Vec4; 6],
+}
+
+impl Frustum {
+    // Extracts the planes of the view volume from a projection matrix (Gribb & Hartmann).
+    // The renderer maps depth to [0, 1], so the near plane comes from the third row alone.
+    pub fn from_projection(projection: &Mat4) -> Frustum {
+        let row = |idx: usize| Vec4::new(projection[idx][0], projection[idx][1], projection[idx][2], projection[idx][3]);
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+
+        return Frustum {planes: [
+            Frustum::normalize_plane(w + x),     // Left
+            Frustum::normalize_plane(w - x),     // Right
+            Frustum::normalize_plane(w + y),     // Bottom
+            Frustum::normalize_plane(w - y),     // Top
+            Frustum::normalize_plane(z),         // Near
+            Frustum::normalize_plane(w - z),     // Far
+        ]};
+    }
+
+    fn normalize_plane(plane: Vec4) -> Vec4 {
+        let length = (plane[0]*plane[0] + plane[1]*plane[1] + plane[2]*plane[2]).sqrt();
+
+        if length == 0.0 {
+            return plane;
+        }
+        return plane / length;
+    }
+
+    pub fn signed_distance(plane: &Vec4, point: Vec3) -> f64 {
+        return plane[0]*point[0] + plane[1]*point[1] + plane[2]*point[2] + plane[3];
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        return self.planes.iter().all(|plane| Frustum::signed_distance(plane, point) >= 0.0);
+    }
+
+    // Conservative: may report spheres near the frustum corners as visible, never the reverse
+    pub fn intersects_sphere(&self, sphere: &BoundingSphere) -> bool {
+        return self.planes.iter().all(|plane| Frustum::signed_distance(plane, sphere.center) >= -sphere.radius);
+    }
+
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        for plane in &self.planes {
+            // The corner furthest along the plane normal
+            let positive = Vec3::new(
+                if plane[0] >= 0.0 { aabb.max[0] } else { aabb.min[0] },
+                if plane[1] >= 0.0 { aabb.max[1] } else { aabb.min[1] },
+                if plane[2] >= 0.0 { aabb.max[2] } else { aabb.min[2] },
+            );
+
+            if Frustum::signed_distance(plane, positive) < 0.0 {
+                return false;
+            }
+        }
+        return true;
+    }
+}
@@ -0,0 +1,171 @@
+use std::f64::consts::PI;
+
+use super::font::OverlayText;
+
+// Screen-space shapes drawn after the 3D pass, in the HUD's own coordinates
+#[derive(Clone, Debug)]
+pub enum HudElement {
+    Line(f64, f64, f64, f64),
+    Polyline(Vec<(f64, f64)>, bool),     // Points and whether to close the loop
+    Rect(f64, f64, f64, f64),            // Left, bottom, width, height
+    Circle(f64, f64, f64, usize),        // Center x, center y, radius, segments
+    Text(OverlayText),
+}
+
+#[derive(Clone, Debug)]
+pub struct HudItem {
+    pub element: HudElement,
+    pub visible: bool,
+}
+
+// The HUD rectangle `left..right` x `bottom..top` is stretched over the whole [-1, 1] output.
+// With aspect correction the layer is also scaled like the projection scales x, so circles stay
+// round on non-square displays and the HUD's x range shrinks or grows with the aspect ratio.
+#[derive(Clone, Debug)]
+pub struct Hud {
+    pub left: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub top: f64,
+    pub aspect_correction: bool,
+    pub visible: bool,
+    items: Vec<Option<HudItem>>,         // Removed items leave a hole so IDs stay stable
+}
+
+impl HudElement {
+    // Segments as (x0, y0, x1, y1) in HUD coordinates; text is handled by `Hud::lines`
+    fn segments(&self) -> Vec::<(f64, f64, f64, f64)> {
+        let mut segments: Vec::<(f64, f64, f64, f64)> = Vec::new();
+
+        match self {
+            HudElement::Line(x0, y0, x1, y1) => segments.push((*x0, *y0, *x1, *y1)),
+            HudElement::Polyline(points, closed) => {
+                for pair in points.windows(2) {
+                    segments.push((pair[0].0, pair[0].1, pair[1].0, pair[1].1));
+                }
+                if *closed && points.len() > 2 {
+                    let (first, last) = (points[0], points[points.len() - 1]);
+                    segments.push((last.0, last.1, first.0, first.1));
+                }
+            },
+            HudElement::Rect(left, bottom, width, height) => {
+                let (right, top) = (left + width, bottom + height);
+                segments.push((*left, *bottom, right, *bottom));
+                segments.push((right, *bottom, right, top));
+                segments.push((right, top, *left, top));
+                segments.push((*left, top, *left, *bottom));
+            },
+            HudElement::Circle(center_x, center_y, radius, count) => {
+                let count = (*count).max(3);
+
+                for idx in 0..count {
+                    let start = 2.0 * PI * idx as f64 / count as f64;
+                    let end = 2.0 * PI * (idx + 1) as f64 / count as f64;
+                    segments.push((
+                        center_x + radius * start.cos(), center_y + radius * start.sin(),
+                        center_x + radius * end.cos(), center_y + radius * end.sin()));
+                }
+            },
+            HudElement::Text(_) => (),
+        }
+
+        return segments;
+    }
+}
+
+impl Hud {
+    pub fn default() -> Hud {
+        return Hud {
+            left: -1.0,
+            right: 1.0,
+            bottom: -1.0,
+            top: 1.0,
+            aspect_correction: false,
+            visible: true,
+            items: Vec::<Option<HudItem>>::new(),
+        };
+    }
+
+    pub fn set_coordinates(&mut self, left: f64, right: f64, bottom: f64, top: f64) -> bool {
+        if left == right || bottom == top {
+            return false;
+        }
+
+        self.left = left;
+        self.right = right;
+        self.bottom = bottom;
+        self.top = top;
+        return true;
+    }
+
+    pub fn add(&mut self, element: HudElement) -> usize {
+        self.items.push(Some(HudItem {element: element, visible: true}));
+        return self.items.len() - 1;
+    }
+
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut HudItem> {
+        return self.items.get_mut(id).and_then(|item| item.as_mut());
+    }
+
+    pub fn remove(&mut self, id: usize) -> bool {
+        return match self.items.get_mut(id) {
+            Some(item) if item.is_some() => {
+                *item = None;
+                true
+            },
+            _ => false,
+        };
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    fn to_output(&self, x: f64, y: f64, aspect: f64) -> (f64, f64) {
+        let mut out_x = -1.0 + 2.0 * (x - self.left) / (self.right - self.left);
+        let out_y = -1.0 + 2.0 * (y - self.bottom) / (self.top - self.bottom);
+
+        if self.aspect_correction {
+            out_x *= aspect;
+        }
+        return (out_x, out_y);
+    }
+
+    // Flat tail_x, tail_y, head_x, head_y segments ready to append to `render_frame`'s output
+    pub fn lines(&self, aspect: f64) -> Vec::<f64> {
+        let mut lines: Vec::<f64> = Vec::<f64>::new();
+
+        if !self.visible {
+            return lines;
+        }
+
+        for item in self.items.iter().flatten().filter(|item| item.visible) {
+            if let HudElement::Text(overlay) = &item.element {
+                // Only the anchor uses HUD coordinates; glyphs are laid out in output space so
+                // they keep their shape whatever the HUD's coordinate ranges are. They are only
+                // narrowed for the aspect ratio when the rest of the layer is too.
+                let (x, y) = self.to_output(overlay.x, overlay.y, aspect);
+                let glyph_aspect = if self.aspect_correction { aspect } else { 1.0 };
+                let mut positioned = overlay.clone();
+                positioned.x = x;
+                positioned.y = y;
+                positioned.style.size *= 2.0 / (self.top - self.bottom).abs();
+
+                lines.extend(positioned.lines(glyph_aspect));
+                continue;
+            }
+
+            for (x0, y0, x1, y1) in item.element.segments() {
+                let (tail_x, tail_y) = self.to_output(x0, y0, aspect);
+                let (head_x, head_y) = self.to_output(x1, y1, aspect);
+
+                lines.push(tail_x);
+                lines.push(tail_y);
+                lines.push(head_x);
+                lines.push(head_y);
+            }
+        }
+
+        return lines;
+    }
+}
@@ -0,0 +1,223 @@
+use log::info;
+
+use super::beam::BEAM_POINT_STRIDE;
+use super::viewport::clip_line;
+
+// Export to the ILDA Image Data Transfer Format used by laser projectors. A file is a run of
+// sections, each a 32-byte header followed by its records, and ends with a header that has no
+// records. All multi-byte values are big-endian.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IldaFormat {
+    Indexed3D = 0,
+    Indexed2D = 1,
+    TrueColor3D = 4,
+    TrueColor2D = 5,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IldaPoint {
+    pub x: i16,
+    pub y: i16,
+    pub z: i16,
+    pub blanked: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct IldaSettings {
+    pub format: IldaFormat,
+    pub points_per_unit: f64,            // Points per unit of length in the [-1, 1] output space
+    pub blank_points: usize,             // Blanked points at the start of each stroke while the galvos settle
+    pub color_index: u8,                 // Palette entry for the indexed formats
+    pub color: (u8, u8, u8),             // Red, green, blue for the true-color formats
+}
+
+// Frames collected over a simulation run, written out as one animation
+#[derive(Clone, Debug)]
+pub struct IldaRecording {
+    pub settings: IldaSettings,
+    pub frames: Vec<Vec<IldaPoint>>,
+    pub active: bool,                    // Whether rendered frames are still being added
+}
+
+// A file counts its frames in 16 bits, so a recording stops itself once it has this many
+pub const MAX_RECORDING_FRAMES: usize = u16::MAX as usize;
+
+const STATUS_LAST_POINT: u8 = 0x80;
+const STATUS_BLANKED: u8 = 0x40;
+
+impl IldaFormat {
+    pub fn from_code(code: u8) -> Option<IldaFormat> {
+        return match code {
+            0 => Some(IldaFormat::Indexed3D),
+            1 => Some(IldaFormat::Indexed2D),
+            4 => Some(IldaFormat::TrueColor3D),
+            5 => Some(IldaFormat::TrueColor2D),
+            _ => None,
+        };
+    }
+
+    fn is_3d(&self) -> bool {
+        return *self == IldaFormat::Indexed3D || *self == IldaFormat::TrueColor3D;
+    }
+
+    fn is_indexed(&self) -> bool {
+        return *self == IldaFormat::Indexed3D || *self == IldaFormat::Indexed2D;
+    }
+}
+
+impl IldaSettings {
+    pub fn default() -> IldaSettings {
+        return IldaSettings {
+            format: IldaFormat::TrueColor2D,
+            points_per_unit: 50.0,
+            blank_points: 3,
+            color_index: 0,
+            color: (255, 255, 255),
+        };
+    }
+}
+
+fn to_coordinate(value: f64) -> i16 {
+    return (value.clamp(-1.0, 1.0) * i16::MAX as f64).round() as i16;
+}
+
+fn to_point(point: (f64, f64), blanked: bool) -> IldaPoint {
+    return IldaPoint {x: to_coordinate(point.0), y: to_coordinate(point.1), z: 0, blanked: blanked};
+}
+
+// Blanked points at `target` while the galvos settle, then a lit copy so the beam turns on
+// exactly where the stroke starts
+fn push_blank_move(points: &mut Vec<IldaPoint>, target: (f64, f64), settings: &IldaSettings) {
+    for _ in 0..settings.blank_points.max(1) {
+        points.push(to_point(target, true));
+    }
+    points.push(to_point(target, false));
+}
+
+// Points for one frame from a path in the layout produced by `beam::optimize`. Drawn segments
+// are subdivided to the requested density and every blanked move lands on `blank_points` points.
+// Only the part of a segment inside the output square is drawn, since everything else would be
+// clamped onto its edge anyway.
+pub fn frame_points(path: &[f64], settings: &IldaSettings) -> Vec<IldaPoint> {
+    let mut points: Vec<IldaPoint> = Vec::new();
+    let mut position = (0.0, 0.0);
+
+    for point in path.chunks_exact(BEAM_POINT_STRIDE) {
+        let target = (point[0], point[1]);
+
+        if point[2] == 0.0 {
+            push_blank_move(&mut points, target, settings);
+            position = target;
+            continue;
+        }
+
+        let clipped = clip_line(position.0, position.1, target.0, target.1, -1.0, -1.0, 1.0, 1.0)
+            .filter(|clipped| clipped.0.is_finite() && clipped.1.is_finite() && clipped.2.is_finite() && clipped.3.is_finite());
+
+        match clipped {
+            Some((from_x, from_y, to_x, to_y)) => {
+                if (from_x, from_y) != position {
+                    push_blank_move(&mut points, (from_x, from_y), settings);
+                }
+
+                let length = ((to_x - from_x).powi(2) + (to_y - from_y).powi(2)).sqrt();
+                let count = ((length * settings.points_per_unit).ceil() as usize).max(1);
+
+                for idx in 1..=count {
+                    let t = idx as f64 / count as f64;
+                    let between = (from_x + (to_x - from_x) * t, from_y + (to_y - from_y) * t);
+                    points.push(to_point(between, false));
+                }
+
+                if (to_x, to_y) != target {
+                    points.push(to_point(target, true));
+                }
+            },
+            None => points.push(to_point(target, true)),
+        }
+        position = target;
+    }
+
+    return points;
+}
+
+fn write_header(out: &mut Vec<u8>, format: IldaFormat, records: u16, frame: u16, total_frames: u16) {
+    out.extend_from_slice(b"ILDA");
+    out.extend_from_slice(&[0, 0, 0, format as u8]);
+    out.extend_from_slice(b"vector  ");                   // Frame name
+    out.extend_from_slice(b"demo    ");                   // Company name
+    out.extend_from_slice(&records.to_be_bytes());
+    out.extend_from_slice(&frame.to_be_bytes());
+    out.extend_from_slice(&total_frames.to_be_bytes());
+    out.extend_from_slice(&[0, 0]);                       // Projector number, reserved
+}
+
+fn write_point(out: &mut Vec<u8>, point: &IldaPoint, last: bool, settings: &IldaSettings) {
+    let mut status: u8 = 0;
+    if last {
+        status |= STATUS_LAST_POINT;
+    }
+    if point.blanked {
+        status |= STATUS_BLANKED;
+    }
+
+    out.extend_from_slice(&point.x.to_be_bytes());
+    out.extend_from_slice(&point.y.to_be_bytes());
+    if settings.format.is_3d() {
+        out.extend_from_slice(&point.z.to_be_bytes());
+    }
+    out.push(status);
+
+    if settings.format.is_indexed() {
+        out.push(settings.color_index);
+    } else {
+        let (red, green, blue) = settings.color;
+        out.extend_from_slice(&[blue, green, red]);
+    }
+}
+
+// A complete file with one section per frame. A section holds at most 65535 points, so longer
+// frames are cut short, and an empty frame gets a single blanked point since a section without
+// records marks the end of the file.
+pub fn write_file(frames: &[Vec<IldaPoint>], settings: &IldaSettings) -> Vec<u8> {
+    let mut out: Vec<u8> = Vec::new();
+    let total_frames = frames.len().min(u16::MAX as usize) as u16;
+
+    let empty = [to_point((0.0, 0.0), true)];
+
+    for (frame_idx, frame) in frames.iter().take(total_frames as usize).enumerate() {
+        let frame: &[IldaPoint] = if frame.is_empty() { &empty } else { frame };
+        let count = frame.len().min(u16::MAX as usize);
+
+        write_header(&mut out, settings.format, count as u16, frame_idx as u16, total_frames);
+        for (idx, point) in frame.iter().take(count).enumerate() {
+            write_point(&mut out, point, idx + 1 == count, settings);
+        }
+    }
+
+    write_header(&mut out, settings.format, 0, 0, total_frames);
+    return out;
+}
+
+impl IldaRecording {
+    pub fn new(settings: IldaSettings) -> IldaRecording {
+        return IldaRecording {settings: settings, frames: Vec::new(), active: true};
+    }
+
+    pub fn add_frame(&mut self, path: &[f64]) {
+        if !self.active {
+            return;
+        }
+
+        self.frames.push(frame_points(path, &self.settings));
+        if self.frames.len() >= MAX_RECORDING_FRAMES {
+            info!("ILDA recording stopped at the format's limit of {} frames", MAX_RECORDING_FRAMES);
+            self.active = false;
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        return write_file(&self.frames, &self.settings);
+    }
+}
@@ -0,0 +1,296 @@
+use std::string::String;
+
+use log::debug;
+
+use super::euler::{Vec3, Mat4, x_rotation_matrix, y_rotation_matrix, z_rotation_matrix};
+use super::renderer::{GameObject, Camera};
+use super::physics::shift;
+
+// Arcade-style controls. JS forwards raw events as they arrive; once per frame `update` folds
+// them through the bindings into named actions, and drives turn those actions into motion of
+// objects or the camera. Gameplay code can also read the actions directly.
+
+// One physical input. Keys use the `KeyboardEvent.code` names ("ArrowLeft", "KeyZ", ...).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+    Key(String),
+    GamepadButton {pad: usize, button: usize},
+    GamepadAxis {pad: usize, axis: usize},
+    Spinner(usize),                      // Relative input; a trackball is two spinners
+    Coin(usize),                         // Coin switch of one slot
+}
+
+#[derive(Clone, Copy, Debug)]
+struct SourceState {
+    down: bool,
+    presses: usize,                      // Since the last `update`, so a quick coin pulse is not lost
+    axis: f64,
+    delta: f64,                          // Spinner movement since the last `update`
+}
+
+#[derive(Clone, Debug)]
+pub struct Binding {
+    pub source: Source,
+    pub action: String,
+    pub scale: f64,                      // Negative to invert, e.g. the left arrow on a "turn" action
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ActionState {
+    pub value: f64,                      // Buttons and axes together stay in [-1, 1]; spinners add on top
+    pub down: bool,
+    pub pressed: bool,                   // Went down this frame
+    pub released: bool,                  // Went up this frame
+}
+
+// What a drive does with its action's value each frame, in the target's own axes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Motion {
+    MoveX,
+    MoveY,
+    MoveZ,
+    Yaw,                                 // About the local y axis
+    Pitch,                               // About the local x axis
+    Roll,                                // About the local z axis
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DriveTarget {
+    Camera,
+    Object(usize),
+}
+
+#[derive(Clone, Debug)]
+pub struct Drive {
+    pub target: DriveTarget,
+    pub motion: Motion,
+    pub action: String,
+    pub rate: f64,                       // Units or radians per frame at full value
+}
+
+pub struct InputMap {
+    pub bindings: Vec<Binding>,
+    pub drives: Vec<Drive>,
+    pub axis_deadzone: f64,              // Gamepad axes closer to rest than this read as zero
+    sources: Vec<(Source, SourceState)>,
+    actions: Vec<(String, ActionState)>,
+}
+
+impl Source {
+    // Accepts "key:<code>", "button:<pad>:<button>", "axis:<pad>:<axis>", "spinner:<index>" or
+    // "coin:<slot>"
+    pub fn parse(name: &str) -> Option<Source> {
+        let (kind, rest) = name.trim().split_once(':')?;
+
+        if kind == "key" {
+            return if rest.is_empty() { None } else { Some(Source::Key(String::from(rest))) };
+        }
+
+        let indices = rest.split(':').map(|field| field.trim().parse::<usize>().ok()).collect::<Option<Vec::<usize>>>()?;
+        return match (kind, indices.as_slice()) {
+            ("button", &[pad, button]) => Some(Source::GamepadButton {pad: pad, button: button}),
+            ("axis", &[pad, axis]) => Some(Source::GamepadAxis {pad: pad, axis: axis}),
+            ("spinner", &[index]) => Some(Source::Spinner(index)),
+            ("coin", &[slot]) => Some(Source::Coin(slot)),
+            _ => None,
+        };
+    }
+}
+
+impl SourceState {
+    fn default() -> SourceState {
+        return SourceState {down: false, presses: 0, axis: 0.0, delta: 0.0};
+    }
+}
+
+impl ActionState {
+    pub fn default() -> ActionState {
+        return ActionState {value: 0.0, down: false, pressed: false, released: false};
+    }
+}
+
+impl Motion {
+    // Accepts "move_x", "move_y", "move_z", "yaw", "pitch" or "roll"
+    pub fn parse(name: &str) -> Option<Motion> {
+        return match name {
+            "move_x" => Some(Motion::MoveX),
+            "move_y" => Some(Motion::MoveY),
+            "move_z" => Some(Motion::MoveZ),
+            "yaw" => Some(Motion::Yaw),
+            "pitch" => Some(Motion::Pitch),
+            "roll" => Some(Motion::Roll),
+            _ => None,
+        };
+    }
+
+    // The move in the target's own axes, or None for a rotation
+    fn offset(self, amount: f64) -> Option<Vec3> {
+        return match self {
+            Motion::MoveX => Some(Vec3::new(amount, 0.0, 0.0)),
+            Motion::MoveY => Some(Vec3::new(0.0, amount, 0.0)),
+            Motion::MoveZ => Some(Vec3::new(0.0, 0.0, amount)),
+            _ => None,
+        };
+    }
+
+    fn rotation(self, amount: f64) -> Mat4 {
+        return match self {
+            Motion::Yaw => y_rotation_matrix(amount),
+            Motion::Pitch => x_rotation_matrix(amount),
+            Motion::Roll => z_rotation_matrix(amount),
+            _ => Mat4::identity(),
+        };
+    }
+
+    // Moves are turned into the object's own axes, and rotations go on the right so they turn
+    // about those axes too
+    fn apply_to_object(self, object: &mut GameObject, amount: f64) {
+        match self.offset(amount) {
+            Some(offset) => shift(object, object.orientation.transform_direction(offset)),
+            None => object.orientation = object.orientation * self.rotation(amount),
+        }
+    }
+
+    // The same for the camera, which has no body to keep in step
+    fn apply_to_camera(self, camera: &mut Camera, amount: f64) {
+        match self.offset(amount) {
+            Some(offset) => camera.position = camera.position + camera.orientation.transform_direction(offset),
+            None => camera.orientation = camera.orientation * self.rotation(amount),
+        }
+    }
+}
+
+impl InputMap {
+    pub fn default() -> InputMap {
+        return InputMap {
+            bindings: Vec::<Binding>::new(),
+            drives: Vec::<Drive>::new(),
+            axis_deadzone: 0.15,
+            sources: Vec::new(),
+            actions: Vec::new(),
+        };
+    }
+
+    fn source_mut(&mut self, source: Source) -> &mut SourceState {
+        let idx = match self.sources.iter().position(|(known, _)| *known == source) {
+            Some(idx) => idx,
+            None => {
+                self.sources.push((source, SourceState::default()));
+                self.sources.len() - 1
+            },
+        };
+
+        return &mut self.sources[idx].1;
+    }
+
+    // Keys, gamepad buttons and coin switches
+    pub fn set_button(&mut self, source: Source, down: bool) {
+        let state = self.source_mut(source);
+
+        if down && !state.down {
+            state.presses += 1;
+        }
+        state.down = down;
+    }
+
+    pub fn set_axis(&mut self, source: Source, value: f64) {
+        self.source_mut(source).axis = value.clamp(-1.0, 1.0);
+    }
+
+    pub fn add_delta(&mut self, source: Source, delta: f64) {
+        self.source_mut(source).delta += delta;
+    }
+
+    pub fn bind(&mut self, source: Source, action: &str, scale: f64) {
+        debug!("Binding {:?} to \"{}\" with scale {}", source, action, scale);
+
+        if !self.actions.iter().any(|(name, _)| name == action) {
+            self.actions.push((String::from(action), ActionState::default()));
+        }
+        self.bindings.push(Binding {source: source, action: String::from(action), scale: scale});
+    }
+
+    // Removes every binding of `source`
+    pub fn unbind(&mut self, source: &Source) -> bool {
+        let count = self.bindings.len();
+        self.bindings.retain(|binding| binding.source != *source);
+        return self.bindings.len() != count;
+    }
+
+    pub fn action(&self, name: &str) -> ActionState {
+        return self.actions.iter().find(|(action, _)| action == name).map_or(ActionState::default(), |(_, state)| *state);
+    }
+
+    // Latches everything received since the last call into the action states. Called once at
+    // the start of a frame.
+    pub fn update(&mut self) {
+        for (name, state) in self.actions.iter_mut() {
+            let mut absolute = 0.0;
+            let mut relative = 0.0;
+            let mut down = false;
+            let mut presses = 0;
+
+            for binding in self.bindings.iter().filter(|binding| binding.action == *name) {
+                let source = match self.sources.iter().find(|(source, _)| *source == binding.source) {
+                    Some((_, source)) => source,
+                    None => continue,
+                };
+
+                match binding.source {
+                    Source::GamepadAxis {..} => {
+                        if source.axis.abs() > self.axis_deadzone {
+                            absolute += source.axis * binding.scale;
+                            down = true;
+                        }
+                    },
+                    Source::Spinner(_) => {
+                        if source.delta != 0.0 {
+                            relative += source.delta * binding.scale;
+                            down = true;
+                        }
+                    },
+                    _ => {
+                        if source.down {
+                            absolute += binding.scale;
+                            down = true;
+                        }
+                        presses += source.presses;
+                    },
+                }
+            }
+
+            let was_down = state.down;
+            *state = ActionState {
+                value: absolute.clamp(-1.0, 1.0) + relative,
+                down: down,
+                pressed: !was_down && (down || presses > 0),
+                released: was_down && !down,
+            };
+        }
+
+        for (_, source) in self.sources.iter_mut() {
+            source.presses = 0;
+            source.delta = 0.0;
+        }
+    }
+
+    // Moves the camera and objects by their drives, using the states from the last `update`.
+    // Objects are placed directly, without gaining velocity, whichever integrator moves them.
+    pub fn apply(&self, objects: &mut [GameObject], camera: &mut Camera) {
+        for drive in &self.drives {
+            let amount = self.action(&drive.action).value * drive.rate;
+            if amount == 0.0 {
+                continue;
+            }
+
+            match drive.target {
+                DriveTarget::Camera => drive.motion.apply_to_camera(camera, amount),
+                DriveTarget::Object(object_id) => {
+                    if let Some(object) = objects.get_mut(object_id) {
+                        drive.motion.apply_to_object(object, amount);
+                    }
+                },
+            }
+        }
+    }
+}
@@ -1,2 +1,23 @@
 pub mod euler;
 pub mod renderer;
+pub mod batch;
+pub mod bounds;
+pub mod frustum;
+pub mod picking;
+pub mod collision;
+pub mod physics;
+pub mod animation;
+pub mod skeleton;
+pub mod morph;
+pub mod font;
+pub mod hud;
+pub mod viewport;
+pub mod stereo;
+pub mod beam;
+pub mod scope;
+pub mod ilda;
+pub mod error;
+pub mod stats;
+pub mod input;
+
+pub use self::error::Error;
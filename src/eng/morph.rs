@@ -0,0 +1,184 @@
+use super::euler::Vec3;
+use super::renderer::GameObject;
+
+// Alternative point arrays for one object, all sharing the object's `connections`.
+// The drawn points are the base plus the weighted offsets of every target.
+#[derive(Clone, Debug)]
+pub struct Morph {
+    pub object_id: usize,
+    pub base: Vec<Vec3>,
+    pub targets: Vec<Vec<Vec3>>,
+    pub weights: Vec<f64>,
+    original: GameObject,                // The object's wireframe before any resampling
+    shapes: Vec<GameObject>,             // Every target as it was added, to resample them again
+}
+
+impl Morph {
+    pub fn new(object_id: usize, object: &GameObject) -> Morph {
+        return Morph {
+            object_id: object_id,
+            base: object.points.clone(),
+            targets: Vec::new(),
+            weights: Vec::new(),
+            original: object.clone(),
+            shapes: Vec::new(),
+        };
+    }
+
+    // Returns the index of the target, or None if it has a different number of points
+    pub fn add_target(&mut self, target: Vec<Vec3>) -> Option<usize> {
+        if target.len() != self.base.len() {
+            return None;
+        }
+
+        self.targets.push(target);
+        self.weights.push(0.0);
+        return Some(self.targets.len() - 1);
+    }
+
+    // Adds a whole wireframe as a target and returns its index. If any target differs in topology
+    // from the object, the object and every target are resampled together into at least
+    // `min_segments` matching segments, and `object` gets the new points and connections; the
+    // weights are kept. Returns None and changes nothing if a wireframe has no segments.
+    pub fn add_shape(&mut self, object: &mut GameObject, shape: GameObject, min_segments: usize) -> Option<usize> {
+        let mut shapes = self.shapes.clone();
+        shapes.push(shape);
+
+        if shapes.iter().all(|shape| same_topology(&self.original, shape)) {
+            let idx = self.add_target(shapes[shapes.len() - 1].points.clone())?;
+            self.shapes = shapes;
+            return Some(idx);
+        }
+
+        let (base, targets, connections) = resample(&self.original, &shapes, min_segments);
+        if base.is_empty() {
+            return None;
+        }
+
+        self.base = base;
+        self.targets = targets;
+        self.weights.push(0.0);
+        self.shapes = shapes;
+
+        object.points = self.blend();
+        object.connections = connections;
+        return Some(self.targets.len() - 1);
+    }
+
+    pub fn set_weight(&mut self, target: usize, weight: f64) -> bool {
+        return match self.weights.get_mut(target) {
+            Some(existing) => {
+                *existing = weight;
+                true
+            },
+            None => false,
+        };
+    }
+
+    pub fn blend(&self) -> Vec::<Vec3> {
+        let mut points = self.base.clone();
+
+        for (target, weight) in self.targets.iter().zip(self.weights.iter()) {
+            if *weight == 0.0 {
+                continue;
+            }
+
+            for (idx, point) in points.iter_mut().enumerate() {
+                *point = *point + (target[idx] - self.base[idx]) * *weight;
+            }
+        }
+
+        return points;
+    }
+
+    pub fn update(&self, objects: &mut [GameObject]) {
+        if let Some(object) = objects.get_mut(self.object_id) {
+            object.points = self.blend();
+        }
+    }
+}
+
+pub fn same_topology(a: &GameObject, b: &GameObject) -> bool {
+    return a.points.len() == b.points.len() && a.connections == b.connections;
+}
+
+fn edges(points: &[Vec3], connections: &[usize]) -> Vec::<(Vec3, Vec3)> {
+    return connections.chunks_exact(2)
+        .filter(|pair| pair[0] < points.len() && pair[1] < points.len())
+        .map(|pair| (points[pair[0]], points[pair[1]]))
+        .collect();
+}
+
+// Halves the longest edge until there are `count` of them, so the detail ends up evenly spread
+fn subdivide(mut edges: Vec::<(Vec3, Vec3)>, count: usize) -> Vec::<(Vec3, Vec3)> {
+    while !edges.is_empty() && edges.len() < count {
+        let longest = (0..edges.len())
+            .max_by(|&i, &j| (edges[i].1 - edges[i].0).length().total_cmp(&(edges[j].1 - edges[j].0).length()))
+            .unwrap();
+
+        let (tail, head) = edges[longest];
+        let middle = (tail + head) * 0.5;
+
+        edges[longest] = (tail, middle);
+        edges.insert(longest + 1, (middle, head));
+    }
+
+    return edges;
+}
+
+// Rewrites arbitrary wireframes as lists of the same number of disconnected segments. Each
+// segment of `a` is paired with the nearest remaining segment of every target, so all of them can
+// morph into each other. Returns the points for `a`, the points for each target and the
+// connections they all share, or nothing if any wireframe has no segments.
+pub fn resample(a: &GameObject, targets: &[GameObject], min_segments: usize) -> (Vec::<Vec3>, Vec::<Vec::<Vec3>>, Vec::<usize>) {
+    let edges_a = edges(&a.points, &a.connections);
+    let target_edges: Vec::<Vec::<(Vec3, Vec3)>> = targets.iter().map(|target| edges(&target.points, &target.connections)).collect();
+
+    if edges_a.is_empty() || target_edges.iter().any(|edges| edges.is_empty()) {
+        return (Vec::new(), Vec::new(), Vec::new());
+    }
+
+    let count = target_edges.iter().map(|edges| edges.len()).fold(min_segments.max(edges_a.len()), usize::max);
+    let edges_a = subdivide(edges_a, count);
+
+    let mut points_a: Vec::<Vec3> = Vec::<Vec3>::with_capacity(2 * count);
+    let mut connections: Vec::<usize> = Vec::<usize>::with_capacity(2 * count);
+
+    for (tail_a, head_a) in edges_a.iter() {
+        connections.push(points_a.len());
+        connections.push(points_a.len() + 1);
+        points_a.push(*tail_a);
+        points_a.push(*head_a);
+    }
+
+    let points_targets = target_edges.into_iter().map(|edges| pair_up(&edges_a, subdivide(edges, count))).collect();
+    return (points_a, points_targets, connections);
+}
+
+// Points of `edges_b` in the order of `edges_a`, each segment of `a` taking the nearest one left
+fn pair_up(edges_a: &[(Vec3, Vec3)], mut remaining_b: Vec::<(Vec3, Vec3)>) -> Vec::<Vec3> {
+    let mut points_b: Vec::<Vec3> = Vec::<Vec3>::with_capacity(2 * edges_a.len());
+
+    for &(tail_a, head_a) in edges_a {
+        let middle_a = (tail_a + head_a) * 0.5;
+
+        let nearest = (0..remaining_b.len())
+            .min_by(|&i, &j| {
+                let distance_i = ((remaining_b[i].0 + remaining_b[i].1) * 0.5 - middle_a).length();
+                let distance_j = ((remaining_b[j].0 + remaining_b[j].1) * 0.5 - middle_a).length();
+                return distance_i.total_cmp(&distance_j);
+            })
+            .unwrap();
+        let (tail_b, head_b) = remaining_b.swap_remove(nearest);
+
+        // Flip the segment if that keeps the endpoints from crossing over during the morph
+        let straight = (tail_b - tail_a).length() + (head_b - head_a).length();
+        let flipped = (head_b - tail_a).length() + (tail_b - head_a).length();
+        let (tail_b, head_b) = if flipped < straight { (head_b, tail_b) } else { (tail_b, head_b) };
+
+        points_b.push(tail_b);
+        points_b.push(head_b);
+    }
+
+    return points_b;
+}
@@ -0,0 +1,239 @@
+use super::euler::{Vec3, axis_angle_rotation_matrix, rotation_vector};
+use super::renderer::GameObject;
+use super::collision::Contact;
+
+// Time is measured in ticks, so a body moves by `velocity` per step just like `GameObject::tick`
+#[derive(Clone, Copy, Debug)]
+pub struct RigidBody {
+    pub inverse_mass: f64,               // Zero makes the body immovable
+    pub inverse_inertia: f64,            // Scalar, i.e. the body is treated as a uniform sphere
+    pub restitution: f64,
+    pub linear_damping: f64,             // Fraction of velocity lost per tick
+    pub angular_damping: f64,
+    pub angular_velocity: Vec3,          // Rotation vector, radians per tick
+    pub force: Vec3,
+    pub torque: Vec3,
+    previous_position: Option<Vec3>,     // Only used by the Verlet integrator
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Integrator {
+    SemiImplicitEuler,
+    Verlet,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct PhysicsWorld {
+    pub gravity: Vec3,
+    pub timestep: f64,
+    pub integrator: Integrator,
+    pub correction_percent: f64,         // Share of the penetration removed per step
+    pub correction_slop: f64,            // Penetration tolerated before correcting
+}
+
+impl RigidBody {
+    pub fn new(mass: f64, radius: f64, restitution: f64, linear_damping: f64, angular_damping: f64) -> RigidBody {
+        let inverse_mass = if mass > 0.0 { 1.0 / mass } else { 0.0 };
+        let inertia = 0.4 * mass * radius * radius;
+        let inverse_inertia = if inertia > 0.0 { 1.0 / inertia } else { 0.0 };
+
+        return RigidBody {
+            inverse_mass: inverse_mass,
+            inverse_inertia: inverse_inertia,
+            restitution: restitution,
+            linear_damping: linear_damping,
+            angular_damping: angular_damping,
+            angular_velocity: Vec3::default(),
+            force: Vec3::default(),
+            torque: Vec3::default(),
+            previous_position: None,
+        };
+    }
+
+    pub fn default() -> RigidBody {
+        return RigidBody::new(1.0, 1.0, 0.5, 0.0, 0.0);
+    }
+
+    // Sized to the object's bounding sphere, picking up any spin it already has
+    pub fn for_object(object: &GameObject, mass: f64, restitution: f64, linear_damping: f64, angular_damping: f64) -> RigidBody {
+        let mut body = RigidBody::new(mass, object.bounding_sphere().radius, restitution, linear_damping, angular_damping);
+        body.angular_velocity = rotation_vector(object.angular_velocity);
+        return body;
+    }
+
+    pub fn is_static(&self) -> bool {
+        return self.inverse_mass == 0.0;
+    }
+
+    pub fn apply_force(&mut self, force: Vec3) {
+        self.force = self.force + force;
+    }
+
+    pub fn apply_torque(&mut self, torque: Vec3) {
+        self.torque = self.torque + torque;
+    }
+
+    pub fn clear_forces(&mut self) {
+        self.force = Vec3::default();
+        self.torque = Vec3::default();
+    }
+}
+
+impl PhysicsWorld {
+    pub fn new(gravity: Vec3, timestep: f64, integrator: Integrator) -> PhysicsWorld {
+        return PhysicsWorld {
+            gravity: gravity,
+            timestep: timestep,
+            integrator: integrator,
+            correction_percent: 0.8,
+            correction_slop: 0.01,
+        };
+    }
+
+    pub fn default() -> PhysicsWorld {
+        return PhysicsWorld::new(Vec3::default(), 1.0, Integrator::SemiImplicitEuler);
+    }
+
+    // Instant change in velocity; `offset` is from the center of mass to where the impulse lands
+    pub fn apply_impulse(&self, object: &mut GameObject, impulse: Vec3, offset: Vec3) {
+        if let Some(body) = object.body.as_mut() {
+            let delta_velocity = impulse * body.inverse_mass;
+            object.velocity = object.velocity + delta_velocity;
+            body.angular_velocity = body.angular_velocity + offset.cross(impulse) * body.inverse_inertia;
+
+            // Verlet derives velocity from the distance to the previous position over one step,
+            // so move that instead
+            if let Some(previous) = body.previous_position {
+                body.previous_position = Some(previous - delta_velocity * self.timestep);
+            }
+        }
+    }
+
+    // Objects without a body keep their constant-velocity `tick`
+    pub fn step(&self, objects: &mut [GameObject]) {
+        for object in objects.iter_mut() {
+            if object.body.is_none() {
+                object.tick();
+                continue;
+            }
+
+            match self.integrator {
+                Integrator::SemiImplicitEuler => self.integrate_euler(object),
+                Integrator::Verlet => self.integrate_verlet(object),
+            }
+            self.integrate_rotation(object);
+
+            if let Some(body) = object.body.as_mut() {
+                body.clear_forces();
+            }
+        }
+    }
+
+    fn acceleration(&self, body: &RigidBody) -> Vec3 {
+        if body.is_static() {
+            return Vec3::default();
+        }
+        return self.gravity + body.force * body.inverse_mass;
+    }
+
+    fn integrate_euler(&self, object: &mut GameObject) {
+        let body = object.body.unwrap();
+        let dt = self.timestep;
+
+        if body.is_static() {
+            object.velocity = Vec3::default();
+            return;
+        }
+
+        object.velocity = (object.velocity + self.acceleration(&body) * dt) * (1.0 - body.linear_damping).max(0.0);
+        object.position = object.position + object.velocity * dt;
+    }
+
+    fn integrate_verlet(&self, object: &mut GameObject) {
+        let mut body = object.body.unwrap();
+        let dt = self.timestep;
+
+        if body.is_static() {
+            object.velocity = Vec3::default();
+            return;
+        }
+
+        let previous = body.previous_position.unwrap_or(object.position - object.velocity * dt);
+        let retained = (1.0 - body.linear_damping).max(0.0);
+        let next = object.position + (object.position - previous) * retained + self.acceleration(&body) * (dt * dt);
+
+        body.previous_position = Some(object.position);
+        object.velocity = (next - object.position) / dt;
+        object.position = next;
+        object.body = Some(body);
+    }
+
+    fn integrate_rotation(&self, object: &mut GameObject) {
+        let mut body = object.body.unwrap();
+        let dt = self.timestep;
+
+        if !body.is_static() {
+            body.angular_velocity = (body.angular_velocity + body.torque * (body.inverse_inertia * dt))
+                * (1.0 - body.angular_damping).max(0.0);
+        }
+
+        let angle = body.angular_velocity.length() * dt;
+        if angle > 0.0 {
+            object.orientation = axis_angle_rotation_matrix(body.angular_velocity, angle) * object.orientation;
+        }
+
+        object.body = Some(body);
+    }
+
+    // Impulse-based response for contacts between bodies. Objects without a body are treated as
+    // immovable but keep moving on their own, so bullets and scenery push bodies around.
+    pub fn resolve(&self, objects: &mut [GameObject], contacts: &[Contact]) {
+        for contact in contacts {
+            let (body_a, body_b) = match (objects[contact.a].body, objects[contact.b].body) {
+                (None, None) => continue,
+                (a, b) => (a, b),
+            };
+
+            let inverse_mass_a = body_a.map_or(0.0, |body| body.inverse_mass);
+            let inverse_mass_b = body_b.map_or(0.0, |body| body.inverse_mass);
+            let total_inverse_mass = inverse_mass_a + inverse_mass_b;
+
+            if total_inverse_mass == 0.0 {
+                continue;
+            }
+
+            let restitution = body_a.map_or(1.0, |body| body.restitution).min(body_b.map_or(1.0, |body| body.restitution));
+            let relative_velocity = objects[contact.b].velocity - objects[contact.a].velocity;
+            let closing_speed = relative_velocity.dot(contact.normal);
+
+            // Only push apart bodies that are approaching each other
+            if closing_speed < 0.0 {
+                let magnitude = -(1.0 + restitution) * closing_speed / total_inverse_mass;
+                let impulse = contact.normal * magnitude;
+
+                let offset_a = contact.point - objects[contact.a].position;
+                let offset_b = contact.point - objects[contact.b].position;
+                self.apply_impulse(&mut objects[contact.a], impulse * -1.0, offset_a);
+                self.apply_impulse(&mut objects[contact.b], impulse, offset_b);
+            }
+
+            let correction_depth = (contact.depth - self.correction_slop).max(0.0);
+            let correction = contact.normal * (correction_depth * self.correction_percent / total_inverse_mass);
+
+            shift(&mut objects[contact.a], correction * -inverse_mass_a);
+            shift(&mut objects[contact.b], correction * inverse_mass_b);
+        }
+    }
+}
+
+// Moves an object without giving it velocity: Verlet bodies move their history along with it.
+// Used for positional correction and for anything else that places objects directly.
+pub fn shift(object: &mut GameObject, delta: Vec3) {
+    object.position = object.position + delta;
+
+    if let Some(body) = object.body.as_mut() {
+        if let Some(previous) = body.previous_position {
+            body.previous_position = Some(previous + delta);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use log::debug;
+
+use super::euler::{Vec3, Vec4, Mat4, INVERSE_EPSILON};
+use super::bounds::{BoundingSphere, Aabb};
+use super::renderer::{GameObject, Camera, Renderer};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Ray {
+    pub origin: Vec3,
+    pub direction: Vec3,                 // Always unit length
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct PickHit {
+    pub object_id: usize,
+    pub distance: f64,
+    pub point: Vec3,
+}
+
+impl Ray {
+    pub fn new(origin: Vec3, direction: Vec3) -> Ray {
+        return Ray {origin: origin, direction: direction.normalize()};
+    }
+
+    pub fn at(&self, t: f64) -> Vec3 {
+        return self.origin + self.direction * t;
+    }
+
+    // Builds the ray from the camera eye through a point in normalized device coordinates
+    // ([-1, 1] on both axes, the same space `render_frame` writes lines in) by unprojecting
+    // it onto the far plane, so hit distances are measured from the camera. None when the
+    // projection cannot be inverted, e.g. with a zero field of view.
+    pub fn from_screen(renderer: &Renderer, camera: &Camera, screen_x: f64, screen_y: f64) -> Option<Ray> {
+        let view = camera.view_matrix();
+        let inverse_view_projection = match (renderer.PROJECTION_MATRIX * view).try_inverse(INVERSE_EPSILON) {
+            Some(inverse) => inverse,
+            None => {
+                debug!("Cannot pick: the view-projection matrix is singular");
+                return None;
+            },
+        };
+
+        let far_point = inverse_view_projection * Vec4::new(screen_x, screen_y, 1.0, 1.0);
+        let far_point = Vec3::new(far_point[0] / far_point[3], far_point[1] / far_point[3], far_point[2] / far_point[3]);
+        let eye = camera.position;
+
+        return Some(Ray::new(eye, far_point - eye));
+    }
+
+    pub fn transform(&self, matrix: &Mat4) -> Ray {
+        return Ray {
+            origin: matrix.transform_point(self.origin),
+            direction: matrix.transform_direction(self.direction).normalize(),
+        };
+    }
+
+    // Distance along the ray to the first intersection, or zero when starting inside the sphere
+    pub fn intersect_sphere(&self, sphere: &BoundingSphere) -> Option<f64> {
+        let offset = self.origin - sphere.center;
+        let b = offset.dot(self.direction);
+        let c = offset.dot(offset) - sphere.radius * sphere.radius;
+
+        if c > 0.0 && b > 0.0 {
+            return None;
+        }
+
+        let discriminant = b * b - c;
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        return Some((-b - discriminant.sqrt()).max(0.0));
+    }
+
+    // Slab test; also returns zero when the origin is inside the box
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f64> {
+        let mut t_min: f64 = 0.0;
+        let mut t_max: f64 = f64::INFINITY;
+
+        for axis in 0..3 {
+            if self.direction[axis].abs() < 1e-12 {
+                if self.origin[axis] < aabb.min[axis] || self.origin[axis] > aabb.max[axis] {
+                    return None;
+                }
+                continue;
+            }
+
+            let inv_direction = 1.0 / self.direction[axis];
+            let mut t0 = (aabb.min[axis] - self.origin[axis]) * inv_direction;
+            let mut t1 = (aabb.max[axis] - self.origin[axis]) * inv_direction;
+
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            t_min = t_min.max(t0);
+            t_max = t_max.min(t1);
+
+            if t_min > t_max {
+                return None;
+            }
+        }
+
+        return Some(t_min);
+    }
+
+    // Tests against the object's local AABB, which behaves as an oriented box in world space
+    pub fn intersect_object(&self, object: &GameObject) -> Option<(f64, Vec3)> {
+        if object.points.is_empty() {
+            return None;
+        }
+
+        self.intersect_sphere(&object.world_bounding_sphere())?;
+
+        let model = object.model_matrix();
+        // Objects scaled flat along an axis have no inverse and cannot be hit
+        let local_ray = self.transform(&model.try_affine_inverse(INVERSE_EPSILON)?);
+        let local_t = local_ray.intersect_aabb(&object.aabb())?;
+
+        let hit_point = model.transform_point(local_ray.at(local_t));
+        return Some(((hit_point - self.origin).length(), hit_point));
+    }
+}
+
+pub fn pick(objects: &[GameObject], ray: &Ray) -> Option<PickHit> {
+    let mut nearest: Option<PickHit> = None;
+
+    for (object_id, object) in objects.iter().enumerate() {
+        if let Some((distance, point)) = ray.intersect_object(object) {
+            let is_nearer = match nearest {
+                Some(hit) => distance < hit.distance,
+                None => true,
+            };
+
+            if is_nearer {
+                nearest = Some(PickHit {object_id: object_id, distance: distance, point: point});
+            }
+        }
+    }
+
+    return nearest;
+}
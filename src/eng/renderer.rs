@@ -1,8 +1,18 @@
 use std::f64::consts::PI;
+use std::fs;
 use std::string::String;
 
+use log::trace;
+
 use super::euler::{IDENTITY4X4};
 use super::euler::{Vec3, Vec4, Mat3, Mat4};
+use super::batch::BatchProject;
+use super::bounds::{BoundingSphere, Aabb};
+use super::frustum::Frustum;
+use super::picking::Ray;
+use super::physics::RigidBody;
+use super::error::Error;
+use super::stats::{FrameStats, now_ms};
 
 #[derive(Clone, Debug)]
 pub struct GameObject {
@@ -10,8 +20,11 @@ pub struct GameObject {
     pub velocity: Vec3,
     pub orientation: Mat4, 
     pub angular_velocity: Mat4,
+    pub scale: Vec3,
     pub points: Vec<Vec3>,
     pub connections: Vec<usize>,
+    pub body: Option<RigidBody>,         // Objects with a body are moved by `PhysicsWorld` instead of `tick`
+    #[allow(dead_code)]
     pos: Vec4,                           // This contains a 1 in the `w` position
 }
 
@@ -91,15 +104,52 @@ pub fn make_tesseract(side_length: f64) -> GameObject {
         vec_connections);
 }
 
+// The built-in shapes by the names `add_shape` accepts
+pub fn make_shape(name: &str, side_length: f64) -> Result<GameObject, Error> {
+    return match name {
+        "cube" => Ok(make_cube(side_length)),
+        "tesseract" => Ok(make_tesseract(side_length)),
+        "skeleton_1" => Ok(make_skeleton1(side_length)),
+        "sphere" => Ok(make_sphere(side_length / 2.0, 7, 12)),
+        _ => Err(Error::UnknownShape(String::from(name))),
+    };
+}
+
 pub fn make_skeleton1(len: f64) -> GameObject {
-    let vec0 = Vec3::new(-2.0*len, -len, -len);
-    let vec1 = Vec3::new(-len, len, len);
-    let vec2 = Vec3::new(0.0, 0.0, 0.0);
-    let vec3 = Vec3::new(len, len, -len);
-    let vec4 = Vec3::new(2.0*len, -len, len);
+    return super::skeleton::skeleton1(len).to_game_object();
+}
+
+// A latitude/longitude wireframe with `rings` horizontal circles and `segments` meridians
+pub fn make_sphere(radius: f64, rings: usize, segments: usize) -> GameObject {
+    let rings = rings.max(1);
+    let segments = segments.max(3);
+
+    let mut vec_points: Vec<Vec3> = vec![Vec3::new(0.0, radius, 0.0), Vec3::new(0.0, -radius, 0.0)];
+    let mut vec_connections: Vec<usize> = Vec::new();
+
+    for ring in 0..rings {
+        let phi = PI * (ring + 1) as f64 / (rings + 1) as f64;
+
+        for segment in 0..segments {
+            let theta = 2.0 * PI * segment as f64 / segments as f64;
+            vec_points.push(Vec3::new(radius * phi.sin() * theta.cos(), radius * phi.cos(), radius * phi.sin() * theta.sin()));
+        }
+    }
 
-    let vec_points: Vec<Vec3> = vec![vec0, vec1, vec2, vec3, vec4];
-    let vec_connections: Vec<usize> = vec![0, 1, 0, 2, 0, 3, 0, 4, 1, 2, 1, 3, 1, 4, 2, 3, 2, 4, 3, 4];
+    let point_index = |ring: usize, segment: usize| 2 + ring * segments + segment % segments;
+
+    for segment in 0..segments {
+        vec_connections.extend_from_slice(&[0, point_index(0, segment)]);
+        vec_connections.extend_from_slice(&[point_index(rings - 1, segment), 1]);
+
+        for ring in 0..rings {
+            vec_connections.extend_from_slice(&[point_index(ring, segment), point_index(ring, segment + 1)]);
+
+            if ring + 1 < rings {
+                vec_connections.extend_from_slice(&[point_index(ring, segment), point_index(ring + 1, segment)]);
+            }
+        }
+    }
 
     return GameObject::new(
         Vec3::new(0.0, 0.0, 0.0),
@@ -118,8 +168,10 @@ impl GameObject {
             velocity: velocity,
             orientation: orientation,
             angular_velocity: angular_velocity,
+            scale: Vec3::new(1.0, 1.0, 1.0),
             points: points,
             connections: connections,
+            body: None,
             pos: Vec4{elems: [position[0], position[1], position[2], 1.0]}
         }
     }
@@ -130,8 +182,10 @@ impl GameObject {
             velocity: Vec3{..Vec3::default()},
             orientation: IDENTITY4X4,
             angular_velocity: IDENTITY4X4,
+            scale: Vec3::new(1.0, 1.0, 1.0),
             points: Vec::<Vec3>::new(),
             connections: Vec::<usize>::new(),
+            body: None,
             pos: Vec4{..Vec4::default()},
         };
     }
@@ -141,14 +195,107 @@ impl GameObject {
         self.orientation = self.angular_velocity * self.orientation;
     }
 
-    pub fn from_file(filename: String) -> GameObject {
-        return GameObject{..GameObject::default() };
+    pub fn model_matrix(&self) -> Mat4 {
+        return self.linear_matrix().to_mat4(self.position);
+    }
+
+    // Orientation and scale without the translation, applied to points before `position` is added
+    pub fn linear_matrix(&self) -> Mat3 {
+        let scale = super::euler::scale_matrix3(self.scale[0], self.scale[1], self.scale[2]);
+        return self.orientation.upper_left() * scale;
+    }
+
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        return BoundingSphere::from_points(&self.points);
+    }
+
+    pub fn aabb(&self) -> Aabb {
+        return Aabb::from_points(&self.points);
+    }
+
+    pub fn world_bounding_sphere(&self) -> BoundingSphere {
+        return self.bounding_sphere().transform(&self.model_matrix());
+    }
+
+    pub fn world_aabb(&self) -> Aabb {
+        return self.aabb().transform(&self.model_matrix());
+    }
+
+    // Every connection must pair up with another and name an existing point
+    pub fn validate(&self) -> Result<(), Error> {
+        if !self.connections.len().is_multiple_of(2) {
+            return Err(Error::OddConnectionCount(self.connections.len()));
+        }
+
+        for (connection, &index) in self.connections.iter().enumerate() {
+            if index >= self.points.len() {
+                return Err(Error::InvalidConnection {connection: connection, index: index, point_count: self.points.len()});
+            }
+        }
+        return Ok(());
+    }
+
+    pub fn from_file(filename: &str) -> Result<GameObject, Error> {
+        return GameObject::from_obj(&fs::read_to_string(filename)?);
+    }
+
+    // Reads the wireframe parts of a Wavefront OBJ file: `v` lines are points, while `l` lines
+    // and the outlines of `f` faces become connections. Everything else is ignored.
+    pub fn from_obj(source: &str) -> Result<GameObject, Error> {
+        let mut object = GameObject::default();
+
+        for (line_idx, line) in source.lines().enumerate() {
+            let parse_error = |message: String| Error::Parse {line: line_idx + 1, message: message};
+            let mut fields = line.split_whitespace();
+
+            match fields.next() {
+                Some("v") => {
+                    let mut coordinates = [0.0; 3];
+                    for coordinate in coordinates.iter_mut() {
+                        let field = fields.next().ok_or_else(|| parse_error(String::from("a point needs x, y and z")))?;
+                        *coordinate = field.parse::<f64>().map_err(|_| parse_error(format!("\"{}\" is not a number", field)))?;
+                    }
+                    object.points.push(Vec3::new(coordinates[0], coordinates[1], coordinates[2]));
+                },
+                Some(kind @ "l") | Some(kind @ "f") => {
+                    let mut indices: Vec::<usize> = Vec::new();
+
+                    for field in fields {
+                        // Faces may carry texture and normal indices as in "3/1/2"; only the point is needed
+                        let point = field.split('/').next().unwrap_or(field);
+                        let index = point.parse::<i64>().map_err(|_| parse_error(format!("\"{}\" is not an index", field)))?;
+
+                        // OBJ indices start at 1, and negative ones count back from the last point
+                        let resolved = if index > 0 { index - 1 } else { object.points.len() as i64 + index };
+                        if index == 0 || resolved < 0 || resolved as usize >= object.points.len() {
+                            return Err(parse_error(format!("point {} does not exist", index)));
+                        }
+                        indices.push(resolved as usize);
+                    }
+
+                    if indices.len() < 2 {
+                        return Err(parse_error(String::from("a line or face needs at least two points")));
+                    }
+                    for pair in indices.windows(2) {
+                        object.connections.extend_from_slice(&[pair[0], pair[1]]);
+                    }
+                    if kind == "f" && indices.len() > 2 {
+                        object.connections.extend_from_slice(&[indices[indices.len() - 1], indices[0]]);
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        return Ok(object);
     }
 }
 
+#[derive(Clone, Copy, Debug)]
 pub struct Camera {
     pub position: Vec3,
     pub orientation: Mat4,
+    #[allow(dead_code)]
     pos: Vec4
 }
 
@@ -167,6 +314,13 @@ impl Camera {
             pos: Vec4{..Vec4::default()},
         }
     }
+
+    // World to camera space: the inverse of placing the camera at `position` turned by
+    // `orientation`, i.e. R^T (x - p)
+    pub fn view_matrix(&self) -> Mat4 {
+        let rotation = self.orientation.upper_left().transpose();
+        return rotation.to_mat4(rotation * self.position * -1.0);
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -178,6 +332,7 @@ pub struct Line {
 }
 
 #[derive(Clone, Copy)]
+#[allow(non_snake_case)]
 pub struct Renderer {
     pub FOV: f64,
     pub CLOSE_PLANE: f64,
@@ -187,6 +342,7 @@ pub struct Renderer {
     pub PROJECTION_MATRIX: Mat4 
 }
 
+#[allow(non_snake_case)]
 impl Renderer {
     pub fn new(FOV: f64, CLOSE_PLANE: f64, FAR_PLANE: f64, ASP: f64, F: f64) -> Renderer {
         let mat_proj: Mat4 = Mat4 {elems: [
@@ -222,67 +378,149 @@ impl Renderer {
         }
     }
 
+    pub fn set_fov(&mut self, NEW_FOV: f64) {
+        self.FOV = NEW_FOV;
+        self.F = 1.0 / (NEW_FOV / 2.0).tan();
+        self.PROJECTION_MATRIX = Mat4{elems: [
+            self.ASP * self.F, 0.0, 0.0, 0.0,
+            0.0, self.F, 0.0, 0.0,
+            0.0, 0.0, self.FAR_PLANE/(self.FAR_PLANE-self.CLOSE_PLANE), -(self.CLOSE_PLANE*self.FAR_PLANE)/(self.FAR_PLANE-self.CLOSE_PLANE),
+            0.0, 0.0, 1.0, 0.0
+        ]};
+    }
+
+    // Keeps the current field of view, which `set_fov` may have changed from the default
     pub fn update_aspect_ratio(&mut self, NEW_ASP: f64) {
         self.ASP = NEW_ASP;
         self.PROJECTION_MATRIX = Mat4{elems: [
-            NEW_ASP * 1.0/(PI/4.0).tan(), 0.0, 0.0, 0.0,
-            0.0, 1.0/(PI/4.0).tan(), 0.0, 0.0,
-            0.0, 0.0, 1000.0/(1000.0-1.0), -(1.0*1000.0)/(1000.0-1.0),
+            NEW_ASP * self.F, 0.0, 0.0, 0.0,
+            0.0, self.F, 0.0, 0.0,
+            0.0, 0.0, self.FAR_PLANE/(self.FAR_PLANE-self.CLOSE_PLANE), -(self.CLOSE_PLANE*self.FAR_PLANE)/(self.FAR_PLANE-self.CLOSE_PLANE),
             0.0, 0.0, 1.0, 0.0
         ]} 
     }
 
-    pub fn render_frame(& mut self, objects: &Vec::<GameObject>, camera: &Camera) -> Vec::<f64> {
-        let mut vectors_to_render: Vec::<f64> = Vec::<f64>::new(); 
-        
+    pub fn frustum(&self) -> Frustum {
+        return Frustum::from_projection(&self.PROJECTION_MATRIX);
+    }
+
+    pub fn screen_to_ray(&self, camera: &Camera, screen_x: f64, screen_y: f64) -> Option<Ray> {
+        return Ray::from_screen(self, camera, screen_x, screen_y);
+    }
+
+    // Whole-object visibility test in camera space. The bounding sphere is cheap and rejects
+    // most objects; the box of the points is tighter for long, thin objects the sphere lets through.
+    pub fn is_visible(&self, frustum: &Frustum, object: &GameObject, world_to_camera_matrix: &Mat4) -> bool {
+        let model_to_camera = *world_to_camera_matrix * object.model_matrix();
+        let sphere = object.bounding_sphere().transform(&model_to_camera);
+
+        if !frustum.intersects_sphere(&sphere) {
+            return false;
+        }
+        return frustum.intersects_aabb(&object.aabb().transform(&model_to_camera));
+    }
+
+    pub fn render_frame(&mut self, objects: &[GameObject], camera: &Camera) -> Vec::<f64> {
+        return self.render_frame_with::<f64>(objects, camera);
+    }
+
+    // `render_frame` that refuses to draw anything if an object is malformed, instead of quietly
+    // leaving out its broken connections
+    pub fn try_render_frame(&mut self, objects: &[GameObject], camera: &Camera) -> Result<Vec::<f64>, Error> {
         for object in objects {
+            object.validate()?;
+        }
+        return Ok(self.render_frame(objects, camera));
+    }
+
+    // `render_frame` with the per-point transforms and the output in `T`. Scene data stays f64
+    // and culling is done at full precision; only the transformed points are narrowed.
+    pub fn render_frame_with<T: BatchProject>(&mut self, objects: &[GameObject], camera: &Camera) -> Vec::<T> {
+        return self.render_frame_into::<T>(objects, camera, None);
+    }
+
+    // `render_frame_with` that also counts what was drawn and times culling, transforming and
+    // emitting segments. Each stage runs over all objects before the next so the clock is only
+    // read a few times per frame.
+    pub fn render_frame_stats<T: BatchProject>(&mut self, objects: &[GameObject], camera: &Camera) -> (Vec::<T>, FrameStats) {
+        let mut stats = FrameStats::default();
+        let lines = self.render_frame_into::<T>(objects, camera, Some(&mut stats));
+        return (lines, stats);
+    }
+
+    // The untimed path leaves both the clock and the counters alone, so plain `render_frame`
+    // costs (and benchmarks) the same as before stats existed
+    fn render_frame_into<T: BatchProject>(&mut self, objects: &[GameObject], camera: &Camera, mut stats: Option<&mut FrameStats>) -> Vec::<T> {
+        let timed = stats.is_some();
+        let clock = || if timed { now_ms() } else { 0.0 };
+        let mut vectors_to_render: Vec::<T> = Vec::<T>::new(); 
+        
+        let world_to_camera_matrix = camera.view_matrix();
+        let frustum = self.frustum();
+
+        let cull_start = clock();
+        let visible: Vec::<&GameObject> = objects.iter()
+            .filter(|object| self.is_visible(&frustum, object, &world_to_camera_matrix))
+            .collect();
+
+        if let Some(stats) = stats.as_deref_mut() {
+            stats.objects_rendered = visible.len();
+            stats.objects_culled = objects.len() - visible.len();
+        }
+
+        // Projection and view are shared by every object, so only the model part changes below
+        let transform_start = clock();
+        let view_projection = self.PROJECTION_MATRIX * world_to_camera_matrix;
+        let mut projected_points: Vec::<T> = Vec::<T>::new();
+        let mut offsets: Vec::<usize> = Vec::<usize>::with_capacity(visible.len());
+
+        for object in &visible {
+            let mvp: Mat4<T> = (view_projection * object.model_matrix()).cast();
+
+            // Worldspace, cameraspace and projection space in one step, x and y per point
+            offsets.push(projected_points.len());
+            T::project_points(&mvp, &object.points, &mut projected_points);
+        }
+
+        let emit_start = clock();
+        for (object, &offset) in visible.iter().zip(offsets.iter()) {
             let num_connections: usize = object.connections.len();
-            
-            let position = &object.position;
-            let translation = super::euler::translation_matrix(position[0], position[1], position[2]);
-            
-            let world_to_camera_matrix = Mat4{elems: [
-                camera.orientation[0][0], camera.orientation[1][0], camera.orientation[2][0],  -camera.position[0],
-                camera.orientation[0][1], camera.orientation[1][1], camera.orientation[2][1],  -camera.position[1],
-                camera.orientation[0][2], camera.orientation[1][2], camera.orientation[2][2],  -camera.position[2],
-                0.0,                      0.0,                      0.0,                       1.0,
-            ]};
-
-            let mut projected_points: Vec::<Vec4> = Vec::<Vec4>::new();
-
-            for point in &object.points {
-                let mut current_point = Vec4{elems:[point[0], point[1], point[2], 1.0]}; // Add W element to point
-                
-                // Transform to worldspace
-                current_point = object.orientation * current_point;
-                current_point = translation * current_point;
-
-                // Transform to cameraspace
-                current_point = world_to_camera_matrix * current_point;
-
-                // Transform to projection space
-                
-                current_point = self.PROJECTION_MATRIX * current_point;
-                current_point = current_point / current_point[3];
-
-                projected_points.push(current_point);
-            }
+            let projected = &projected_points[offset..offset + 2 * object.points.len()];
            
             for idx in 0..num_connections/2 {
                 let idx1: usize = object.connections[2*idx];
                 let idx2: usize = object.connections[2*idx + 1];
 
+                // Connections to points that do not exist are skipped; `validate` reports them
+                if idx1 >= object.points.len() || idx2 >= object.points.len() {
+                    trace!("Skipping connection {}-{} of an object with {} points", idx1, idx2, object.points.len());
+                    continue;
+                }
 
-                let tail = &projected_points[idx1];
-                let head = &projected_points[idx2];
-                
-                vectors_to_render.push(tail[0]);
-                vectors_to_render.push(tail[1]);
-                vectors_to_render.push(head[0]);
-                vectors_to_render.push(head[1]);
+                let segment = [projected[2*idx1], projected[2*idx1 + 1], projected[2*idx2], projected[2*idx2 + 1]];
+
+                if let Some(stats) = stats.as_deref_mut() {
+                    let [x0, y0, x1, y1] = segment.map(|value| value.to_f64());
+
+                    stats.segments_emitted += 1;
+                    stats.line_length += ((x1 - x0) * (x1 - x0) + (y1 - y0) * (y1 - y0)).sqrt();
+                    if [x0, y0, x1, y1].iter().any(|value| value.abs() > 1.0) {
+                        stats.segments_offscreen += 1;
+                    }
+                }
+
+                vectors_to_render.extend_from_slice(&segment);
             } 
         }
 
+        if let Some(stats) = stats {
+            let end = clock();
+            stats.points_transformed = visible.iter().map(|object| object.points.len()).sum();
+            stats.cull_ms = transform_start - cull_start;
+            stats.transform_ms = emit_start - transform_start;
+            stats.emit_ms = end - emit_start;
+        }
+
         return vectors_to_render;
     }
 
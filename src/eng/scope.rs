@@ -0,0 +1,146 @@
+use super::beam::BEAM_POINT_STRIDE;
+use super::viewport::clip_line;
+
+// Drives an oscilloscope in XY mode from a stereo audio signal: the left channel is x and the
+// right channel is y. The beam follows the samples in order, so every segment is traced by
+// interpolating between its endpoints at a steady density.
+
+// What to do with the moves between separate strokes, since most scopes have no blanking input
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Blanking {
+    Trace,                               // Draw the move like any other segment
+    Jump,                                // Go straight to the next stroke in a single sample
+    Dwell,                               // Jump, then hold still while the beam settles
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ScopeSettings {
+    pub enabled: bool,
+    pub sample_rate: u32,
+    pub points_per_unit: f64,            // Samples per unit of length in the [-1, 1] output space
+    pub blanking: Blanking,
+    pub dwell_samples: usize,            // Samples held after a jump when blanking is `Dwell`
+    pub amplitude: f64,                  // Output scale, 1 maps the output square to full scale
+}
+
+// Channels per frame in the interleaved sample buffer
+pub const SCOPE_CHANNELS: usize = 2;
+
+impl Blanking {
+    pub fn parse(name: &str) -> Option<Blanking> {
+        return match name {
+            "trace" => Some(Blanking::Trace),
+            "jump" => Some(Blanking::Jump),
+            "dwell" => Some(Blanking::Dwell),
+            _ => None,
+        };
+    }
+}
+
+impl ScopeSettings {
+    pub fn default() -> ScopeSettings {
+        return ScopeSettings {
+            enabled: false,
+            sample_rate: 48000,
+            points_per_unit: 200.0,
+            blanking: Blanking::Dwell,
+            dwell_samples: 4,
+            amplitude: 1.0,
+        };
+    }
+}
+
+fn push_sample(samples: &mut Vec::<f32>, point: (f64, f64), amplitude: f64) {
+    samples.push((point.0 * amplitude).clamp(-1.0, 1.0) as f32);
+    samples.push((point.1 * amplitude).clamp(-1.0, 1.0) as f32);
+}
+
+// Samples the line from `from` to `to`, leaving out `from` itself so consecutive segments do not
+// repeat their shared endpoint. Only the part inside the output square is traced, so points
+// projected from near the camera plane cannot ask for millions of samples; the rest is a jump.
+fn push_segment(samples: &mut Vec::<f32>, from: (f64, f64), to: (f64, f64), settings: &ScopeSettings) {
+    let (from_x, from_y, to_x, to_y) = match clip_line(from.0, from.1, to.0, to.1, -1.0, -1.0, 1.0, 1.0) {
+        Some(clipped) if clipped.0.is_finite() && clipped.1.is_finite() && clipped.2.is_finite() && clipped.3.is_finite() => clipped,
+        _ => {
+            push_sample(samples, to, settings.amplitude);
+            return;
+        },
+    };
+
+    if (from_x, from_y) != from {
+        push_sample(samples, (from_x, from_y), settings.amplitude);
+    }
+    trace(samples, (from_x, from_y), (to_x, to_y), settings);
+    if (to_x, to_y) != to {
+        push_sample(samples, to, settings.amplitude);
+    }
+}
+
+fn trace(samples: &mut Vec::<f32>, from: (f64, f64), to: (f64, f64), settings: &ScopeSettings) {
+    let length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+    let count = ((length * settings.points_per_unit).ceil() as usize).max(1);
+
+    for idx in 1..=count {
+        let t = idx as f64 / count as f64;
+        push_sample(samples, (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t), settings.amplitude);
+    }
+}
+
+// Interleaved left/right samples in [-1, 1] for one pass over a path in the layout produced by
+// `beam::optimize`. Looping the buffer redraws the frame, starting and ending at the origin.
+pub fn synthesize(path: &[f64], settings: &ScopeSettings) -> Vec::<f32> {
+    let mut samples: Vec::<f32> = Vec::new();
+    let mut position = (0.0, 0.0);
+
+    let points = path.chunks_exact(BEAM_POINT_STRIDE)
+        .map(|point| ((point[0], point[1]), point[2] != 0.0))
+        .chain(std::iter::once(((0.0, 0.0), false)));
+
+    for (point, draw) in points {
+        if draw || settings.blanking == Blanking::Trace {
+            push_segment(&mut samples, position, point, settings);
+        } else if point != position {
+            let holds = if settings.blanking == Blanking::Dwell { settings.dwell_samples } else { 0 };
+
+            for _ in 0..=holds {
+                push_sample(&mut samples, point, settings.amplitude);
+            }
+        }
+        position = point;
+    }
+
+    return samples;
+}
+
+pub fn to_pcm16(samples: &[f32]) -> Vec::<i16> {
+    return samples.iter().map(|sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16).collect();
+}
+
+// A complete 16-bit stereo PCM WAV file holding the samples
+pub fn to_wav(samples: &[f32], sample_rate: u32) -> Vec::<u8> {
+    let channels = SCOPE_CHANNELS as u16;
+    let block_align = channels * 2;
+    let data_size = (samples.len() * 2) as u32;
+
+    let mut wav: Vec::<u8> = Vec::with_capacity(44 + data_size as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());           // Integer PCM
+    wav.extend_from_slice(&channels.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+    wav.extend_from_slice(&block_align.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    for sample in to_pcm16(samples) {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    return wav;
+}
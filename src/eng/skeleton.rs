@@ -0,0 +1,294 @@
+use std::string::String;
+
+use super::euler::{Vec3, Quat, IDENTITY4X4};
+use super::renderer::GameObject;
+use super::animation::{Track, Easing, LoopMode};
+
+// Each bone ends in a joint. The bone is drawn as a segment from its parent's joint to its own,
+// and rotating a bone swings that segment, and every bone below it, around the parent's joint.
+#[derive(Clone, Debug)]
+pub struct Bone {
+    pub name: String,
+    pub parent: Option<usize>,           // Always an index smaller than this bone's own
+    pub offset: Vec3,                    // Rest position of the joint in the parent's frame
+    pub rest_rotation: Quat,
+    pub rotation: Quat,                  // Posed rotation on top of the rest rotation
+}
+
+#[derive(Clone, Debug)]
+pub struct Skeleton {
+    pub bones: Vec<Bone>,
+    pub segments: Vec<usize>,            // Pairs of joints to draw; `add_bone` adds one per bone
+}
+
+// Joint positions and orientations in the skeleton's model space after forward kinematics
+#[derive(Clone, Debug)]
+pub struct Pose {
+    pub joints: Vec<Vec3>,
+    pub rotations: Vec<Quat>,
+}
+
+impl Bone {
+    pub fn new(name: &str, parent: Option<usize>, offset: Vec3) -> Bone {
+        return Bone {
+            name: String::from(name),
+            parent: parent,
+            offset: offset,
+            rest_rotation: Quat::default(),
+            rotation: Quat::default(),
+        };
+    }
+}
+
+impl Skeleton {
+    pub fn default() -> Skeleton {
+        return Skeleton {bones: Vec::<Bone>::new(), segments: Vec::<usize>::new()};
+    }
+
+    // Returns the index of the new bone, or None if the parent doesn't exist yet
+    pub fn add_bone(&mut self, name: &str, parent: Option<&str>, offset: Vec3) -> Option<usize> {
+        let parent_idx = match parent {
+            Some(parent_name) => Some(self.bone_index(parent_name)?),
+            None => None,
+        };
+
+        self.bones.push(Bone::new(name, parent_idx, offset));
+        let idx = self.bones.len() - 1;
+
+        if let Some(parent_idx) = parent_idx {
+            self.segments.extend_from_slice(&[parent_idx, idx]);
+        }
+        return Some(idx);
+    }
+
+    pub fn bone_index(&self, name: &str) -> Option<usize> {
+        return self.bones.iter().position(|bone| bone.name == name);
+    }
+
+    pub fn set_rotation(&mut self, name: &str, rotation: Quat) -> bool {
+        return match self.bone_index(name) {
+            Some(idx) => {
+                self.bones[idx].rotation = rotation;
+                true
+            },
+            None => false,
+        };
+    }
+
+    pub fn reset_pose(&mut self) {
+        for bone in &mut self.bones {
+            bone.rotation = Quat::default();
+        }
+    }
+
+    pub fn forward_kinematics(&self) -> Pose {
+        let mut joints: Vec::<Vec3> = Vec::<Vec3>::with_capacity(self.bones.len());
+        let mut rotations: Vec::<Quat> = Vec::<Quat>::with_capacity(self.bones.len());
+
+        for bone in &self.bones {
+            let (parent_joint, parent_rotation) = match bone.parent {
+                Some(parent) => (joints[parent], rotations[parent]),
+                None => (Vec3::default(), Quat::default()),
+            };
+
+            let rotation = parent_rotation * bone.rest_rotation * bone.rotation;
+
+            joints.push(parent_joint + rotation.rotate(bone.offset));
+            rotations.push(rotation);
+        }
+
+        return Pose {joints: joints, rotations: rotations};
+    }
+
+    pub fn connections(&self) -> Vec::<usize> {
+        return self.segments.clone();
+    }
+
+    pub fn to_game_object(&self) -> GameObject {
+        return GameObject::new(
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 0.0),
+            IDENTITY4X4,
+            IDENTITY4X4,
+            self.forward_kinematics().joints,
+            self.connections());
+    }
+
+    // Writes the current pose into an object made by `to_game_object`
+    pub fn update_object(&self, object: &mut GameObject) {
+        object.points = self.forward_kinematics().joints;
+        object.connections = self.connections();
+    }
+
+    // Rotates `idx` so that, in model space, the direction `from` turns onto `to` around its
+    // parent's joint
+    fn rotate_bone_towards(&mut self, idx: usize, pose: &Pose, from: Vec3, to: Vec3) {
+        let parent_rotation = match self.bones[idx].parent {
+            Some(parent) => pose.rotations[parent],
+            None => Quat::default(),
+        };
+        let frame = parent_rotation * self.bones[idx].rest_rotation;
+        let delta = Quat::rotation_between(from, to);
+
+        self.bones[idx].rotation = (frame.conjugate() * delta * frame * self.bones[idx].rotation).normalize();
+    }
+
+    // Analytic two-bone IK: turns `end_bone` and its parent so the end joint reaches `target` (or
+    // gets as close as the bone lengths allow), keeping the middle joint on the side of `pole`.
+    // The chain hangs from the joint of the parent's parent, e.g. "left_forearm" bends the arm at
+    // the elbow and swings it from the shoulder. Both points are in the skeleton's model space.
+    // Returns false if the chain is too short.
+    pub fn solve_two_bone_ik(&mut self, end_bone: &str, target: Vec3, pole: Vec3) -> bool {
+        let end = match self.bone_index(end_bone) {
+            Some(end) => end,
+            None => return false,
+        };
+        let middle = match self.bones[end].parent {
+            Some(middle) => middle,
+            None => return false,
+        };
+        let upper = match self.bones[middle].parent {
+            Some(upper) => upper,
+            None => return false,
+        };
+
+        let pose = self.forward_kinematics();
+        let (a, b, c) = (pose.joints[upper], pose.joints[middle], pose.joints[end]);
+        let upper_length = (b - a).length();
+        let lower_length = (c - b).length();
+
+        let to_target = target - a;
+        if to_target.length() < 1e-9 || upper_length < 1e-9 || lower_length < 1e-9 {
+            return false;
+        }
+
+        let direction = to_target.normalize();
+        let reach = to_target.length()
+            .min(upper_length + lower_length - 1e-9)
+            .max((upper_length - lower_length).abs() + 1e-9);
+
+        // Bend direction: the pole projected onto the plane perpendicular to the chain
+        let mut bend = (pole - a) - direction * (pole - a).dot(direction);
+        if bend.length() < 1e-9 {
+            bend = (b - a) - direction * (b - a).dot(direction);
+        }
+        if bend.length() < 1e-9 {
+            let helper = if direction[0].abs() < 0.9 { Vec3::new(1.0, 0.0, 0.0) } else { Vec3::new(0.0, 1.0, 0.0) };
+            bend = direction.cross(helper);
+        }
+        let bend = bend.normalize();
+
+        let cos_upper = ((upper_length * upper_length + reach * reach - lower_length * lower_length) / (2.0 * upper_length * reach)).clamp(-1.0, 1.0);
+        let sin_upper = (1.0 - cos_upper * cos_upper).sqrt();
+
+        let desired_middle = a + direction * (upper_length * cos_upper) + bend * (upper_length * sin_upper);
+        let desired_end = a + direction * reach;
+
+        self.rotate_bone_towards(middle, &pose, b - a, desired_middle - a);
+
+        let pose = self.forward_kinematics();
+        let (b, c) = (pose.joints[middle], pose.joints[end]);
+        self.rotate_bone_towards(end, &pose, c - b, desired_end - b);
+
+        return true;
+    }
+}
+
+// The five points of `make_skeleton1` as a rig: a core joint at the origin with a limb out to
+// each of the other four. Every pair of joints is drawn, as in the original shape.
+pub fn skeleton1(len: f64) -> Skeleton {
+    let mut skeleton = Skeleton::default();
+
+    skeleton.add_bone("core", None, Vec3::new(0.0, 0.0, 0.0));
+    skeleton.add_bone("limb_0", Some("core"), Vec3::new(-2.0*len, -len, -len));
+    skeleton.add_bone("limb_1", Some("core"), Vec3::new(-len, len, len));
+    skeleton.add_bone("limb_2", Some("core"), Vec3::new(len, len, -len));
+    skeleton.add_bone("limb_3", Some("core"), Vec3::new(2.0*len, -len, len));
+
+    // Same segments in the same order as the fixed shape had, with its middle point now joint 0
+    skeleton.segments = vec![1, 2, 1, 0, 1, 3, 1, 4, 2, 0, 2, 3, 2, 4, 0, 3, 0, 4, 3, 4];
+    return skeleton;
+}
+
+// A stick figure standing on the origin's xz-plane with +y up; `len` is roughly a forearm
+pub fn make_stick_figure(len: f64) -> Skeleton {
+    let mut skeleton = Skeleton::default();
+
+    let bones: [(&str, Option<&str>, Vec3); 16] = [
+        ("pelvis", None, Vec3::new(0.0, 2.4 * len, 0.0)),
+        ("spine", Some("pelvis"), Vec3::new(0.0, 1.5 * len, 0.0)),
+        ("neck", Some("spine"), Vec3::new(0.0, 0.4 * len, 0.0)),
+        ("head", Some("neck"), Vec3::new(0.0, 0.6 * len, 0.0)),
+        ("left_shoulder", Some("spine"), Vec3::new(-0.6 * len, 0.0, 0.0)),
+        ("left_upper_arm", Some("left_shoulder"), Vec3::new(0.0, -len, 0.0)),
+        ("left_forearm", Some("left_upper_arm"), Vec3::new(0.0, -len, 0.0)),
+        ("right_shoulder", Some("spine"), Vec3::new(0.6 * len, 0.0, 0.0)),
+        ("right_upper_arm", Some("right_shoulder"), Vec3::new(0.0, -len, 0.0)),
+        ("right_forearm", Some("right_upper_arm"), Vec3::new(0.0, -len, 0.0)),
+        ("left_hip", Some("pelvis"), Vec3::new(-0.4 * len, 0.0, 0.0)),
+        ("left_thigh", Some("left_hip"), Vec3::new(0.0, -1.2 * len, 0.0)),
+        ("left_shin", Some("left_thigh"), Vec3::new(0.0, -1.2 * len, 0.0)),
+        ("right_hip", Some("pelvis"), Vec3::new(0.4 * len, 0.0, 0.0)),
+        ("right_thigh", Some("right_hip"), Vec3::new(0.0, -1.2 * len, 0.0)),
+        ("right_shin", Some("right_thigh"), Vec3::new(0.0, -1.2 * len, 0.0)),
+    ];
+
+    for (name, parent, offset) in bones.iter() {
+        skeleton.add_bone(name, *parent, *offset);
+    }
+
+    return skeleton;
+}
+
+// A skeleton driving the points of one object, with optional keyframed bone rotations
+#[derive(Clone, Debug)]
+pub struct Rig {
+    pub object_id: usize,
+    pub skeleton: Skeleton,
+    pub tracks: Vec<(usize, Track<Quat>)>,
+    pub loop_mode: LoopMode,             // Shared by every bone track, `Once` like other tracks
+}
+
+impl Rig {
+    pub fn new(object_id: usize, skeleton: Skeleton) -> Rig {
+        return Rig {object_id: object_id, skeleton: skeleton, tracks: Vec::new(), loop_mode: LoopMode::Once};
+    }
+
+    pub fn set_loop_mode(&mut self, loop_mode: LoopMode) {
+        self.loop_mode = loop_mode;
+        for (_, track) in self.tracks.iter_mut() {
+            track.loop_mode = loop_mode;
+        }
+    }
+
+    pub fn add_keyframe(&mut self, bone_name: &str, time: f64, rotation: Quat, easing: Easing) -> bool {
+        let bone = match self.skeleton.bone_index(bone_name) {
+            Some(bone) => bone,
+            None => return false,
+        };
+
+        match self.tracks.iter_mut().find(|(idx, _)| *idx == bone) {
+            Some((_, track)) => track.insert(time, rotation, easing),
+            None => {
+                let mut track = Track::new(self.loop_mode);
+                track.insert(time, rotation, easing);
+                self.tracks.push((bone, track));
+            },
+        }
+        return true;
+    }
+
+    pub fn animate(&mut self, time: f64) {
+        for (bone, track) in &self.tracks {
+            if let Some(rotation) = track.sample(time) {
+                self.skeleton.bones[*bone].rotation = rotation;
+            }
+        }
+    }
+
+    pub fn update(&self, objects: &mut [GameObject]) {
+        if let Some(object) = objects.get_mut(self.object_id) {
+            self.skeleton.update_object(object);
+        }
+    }
+}
@@ -0,0 +1,87 @@
+// Counters and timings for one frame, for the debug overlay. The renderer fills in the counts
+// and its own stages; the wrapper adds the stages around it. Times are in milliseconds.
+// Counts are per render pass and summed over passes, so with stereo or extra views an object
+// is counted once for every eye or view it is drawn in.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameStats {
+    pub objects_rendered: usize,
+    pub objects_culled: usize,           // Outside the view frustum
+    pub points_transformed: usize,
+    pub segments_emitted: usize,
+    pub segments_offscreen: usize,       // Emitted but reaching outside the [-1, 1] screen, which nothing clips
+    pub line_length: f64,                // Summed in screen space, roughly what a vector beam has to draw
+    pub cull_ms: f64,
+    pub transform_ms: f64,
+    pub emit_ms: f64,
+    pub simulation_ms: f64,              // Physics, animation, rigs, morphs and collisions
+    pub output_ms: f64,                  // Beam path, scope samples and ILDA frames
+    pub frame_ms: f64,                   // The whole `render_loop`
+}
+
+impl FrameStats {
+    pub fn default() -> FrameStats {
+        return FrameStats {
+            objects_rendered: 0,
+            objects_culled: 0,
+            points_transformed: 0,
+            segments_emitted: 0,
+            segments_offscreen: 0,
+            line_length: 0.0,
+            cull_ms: 0.0,
+            transform_ms: 0.0,
+            emit_ms: 0.0,
+            simulation_ms: 0.0,
+            output_ms: 0.0,
+            frame_ms: 0.0,
+        };
+    }
+
+    // Folds in another render pass, e.g. the second eye or an extra view. Everything is summed,
+    // object counts included.
+    pub fn add(&mut self, other: &FrameStats) {
+        self.objects_rendered += other.objects_rendered;
+        self.objects_culled += other.objects_culled;
+        self.points_transformed += other.points_transformed;
+        self.segments_emitted += other.segments_emitted;
+        self.segments_offscreen += other.segments_offscreen;
+        self.line_length += other.line_length;
+        self.cull_ms += other.cull_ms;
+        self.transform_ms += other.transform_ms;
+        self.emit_ms += other.emit_ms;
+        self.simulation_ms += other.simulation_ms;
+        self.output_ms += other.output_ms;
+        self.frame_ms += other.frame_ms;
+    }
+}
+
+// `std::time::Instant` panics on wasm32-unknown-unknown, so the browser's clock is used there
+#[cfg(target_arch = "wasm32")]
+mod clock {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_namespace = performance)]
+        fn now() -> f64;
+    }
+
+    pub fn now_ms() -> f64 {
+        return now();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod clock {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static START: OnceLock<Instant> = OnceLock::new();
+
+    pub fn now_ms() -> f64 {
+        return START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0;
+    }
+}
+
+// Milliseconds from an arbitrary starting point; only differences are meaningful
+pub use self::clock::now_ms;
@@ -0,0 +1,87 @@
+use super::euler::Vec3;
+use super::renderer::{Camera, Renderer};
+use super::viewport::Viewport;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StereoMode {
+    Off,
+    Anaglyph,                            // Both eyes over the full viewport, told apart by tag
+    SideBySide,                          // Left eye in the left half, right eye in the right half
+}
+
+// Written next to every line so the front-end knows how to draw it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Eye {
+    Both = 0,
+    Left = 1,
+    Right = 2,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct StereoSettings {
+    pub mode: StereoMode,
+    pub interocular: f64,                // Distance between the eyes in world units
+    pub convergence: f64,                // Distance at which both eyes see a point in the same place
+}
+
+impl StereoMode {
+    pub fn parse(name: &str) -> Option<StereoMode> {
+        return match name {
+            "off" => Some(StereoMode::Off),
+            "anaglyph" => Some(StereoMode::Anaglyph),
+            "side_by_side" => Some(StereoMode::SideBySide),
+            _ => None,
+        };
+    }
+}
+
+impl Eye {
+    // -1 for the left eye, 1 for the right
+    fn side(&self) -> f64 {
+        return match self {
+            Eye::Left => -1.0,
+            Eye::Right => 1.0,
+            Eye::Both => 0.0,
+        };
+    }
+}
+
+impl StereoSettings {
+    pub fn new(mode: StereoMode, interocular: f64, convergence: f64) -> StereoSettings {
+        return StereoSettings {mode: mode, interocular: interocular, convergence: convergence};
+    }
+
+    pub fn default() -> StereoSettings {
+        return StereoSettings::new(StereoMode::Off, 6.5, 200.0);
+    }
+
+    // The camera moved half the interocular distance along its own x axis
+    pub fn eye_camera(&self, camera: &Camera, eye: Eye) -> Camera {
+        let offset = camera.orientation.transform_direction(Vec3::new(eye.side() * self.interocular / 2.0, 0.0, 0.0));
+
+        return Camera::new(camera.position + offset, camera.orientation);
+    }
+
+    // Off-axis projection: rather than toeing the eyes in, each frustum is sheared sideways so
+    // the two images line up at the convergence distance without vertical parallax
+    pub fn eye_renderer(&self, renderer: &Renderer, eye: Eye) -> Renderer {
+        let mut eye_renderer = *renderer;
+
+        if self.convergence > 0.0 {
+            let shear = renderer.PROJECTION_MATRIX.elems[0] * eye.side() * self.interocular / (2.0 * self.convergence);
+            eye_renderer.PROJECTION_MATRIX.elems[2] += shear;
+        }
+        return eye_renderer;
+    }
+
+    // Where each eye's picture goes within the viewport it is given
+    pub fn eye_viewport(&self, viewport: &Viewport, eye: Eye) -> Viewport {
+        if self.mode != StereoMode::SideBySide {
+            return *viewport;
+        }
+
+        let half = viewport.width / 2.0;
+        let left = if eye == Eye::Left { viewport.left } else { viewport.left + half };
+        return Viewport::new(left, viewport.bottom, half, viewport.height);
+    }
+}
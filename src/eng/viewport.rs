@@ -0,0 +1,165 @@
+use std::string::String;
+
+use super::batch::BatchProject;
+use super::euler::Scalar;
+use super::renderer::{GameObject, Camera, Renderer};
+use super::stats::FrameStats;
+
+// A rectangle of the output, in the same [-1, 1] space as the line buffer
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub left: f64,
+    pub bottom: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+// A named camera drawn into its own viewport with its own projection settings
+#[derive(Clone, Copy)]
+pub struct View {
+    pub camera: Camera,
+    pub renderer: Renderer,
+    pub viewport: Viewport,
+    pub enabled: bool,
+    pub border: bool,
+}
+
+impl Viewport {
+    pub fn new(left: f64, bottom: f64, width: f64, height: f64) -> Viewport {
+        return Viewport {left: left, bottom: bottom, width: width, height: height};
+    }
+
+    pub fn default() -> Viewport {
+        return Viewport::new(-1.0, -1.0, 2.0, 2.0);
+    }
+
+    pub fn is_full(&self) -> bool {
+        return *self == Viewport::default();
+    }
+
+    // The aspect ratio a renderer needs so the picture isn't stretched inside this viewport
+    pub fn aspect_ratio(&self, output_aspect: f64) -> f64 {
+        return output_aspect * self.height / self.width;
+    }
+
+    // Clips a frame rendered for the whole [-1, 1] space to it, then squeezes it into the viewport.
+    // Works at the precision the frame was rendered in; only the clipping itself is done in f64.
+    pub fn compose<T: Scalar>(&self, lines: &[T], output: &mut Vec::<T>) {
+        for line in lines.chunks_exact(4) {
+            let (x0, y0, x1, y1) = (line[0].to_f64(), line[1].to_f64(), line[2].to_f64(), line[3].to_f64());
+
+            if let Some((x0, y0, x1, y1)) = clip_line(x0, y0, x1, y1, -1.0, -1.0, 1.0, 1.0) {
+                output.push(T::from_f64(self.left + (x0 + 1.0) * self.width / 2.0));
+                output.push(T::from_f64(self.bottom + (y0 + 1.0) * self.height / 2.0));
+                output.push(T::from_f64(self.left + (x1 + 1.0) * self.width / 2.0));
+                output.push(T::from_f64(self.bottom + (y1 + 1.0) * self.height / 2.0));
+            }
+        }
+    }
+
+    pub fn border<T: Scalar>(&self, output: &mut Vec::<T>) {
+        let (right, top) = (self.left + self.width, self.bottom + self.height);
+
+        output.extend([
+            self.left, self.bottom, right, self.bottom,
+            right, self.bottom, right, top,
+            right, top, self.left, top,
+            self.left, top, self.left, self.bottom,
+        ].iter().map(|&value| T::from_f64(value)));
+    }
+}
+
+// Liang-Barsky clipping of a segment against an axis-aligned rectangle
+#[allow(clippy::too_many_arguments)]
+pub fn clip_line(x0: f64, y0: f64, x1: f64, y1: f64, x_min: f64, y_min: f64, x_max: f64, y_max: f64) -> Option<(f64, f64, f64, f64)> {
+    let (dx, dy) = (x1 - x0, y1 - y0);
+    let mut t_enter: f64 = 0.0;
+    let mut t_exit: f64 = 1.0;
+
+    let edges = [
+        (-dx, x0 - x_min),
+        (dx, x_max - x0),
+        (-dy, y0 - y_min),
+        (dy, y_max - y0),
+    ];
+
+    for (p, q) in edges.iter() {
+        if *p == 0.0 {
+            if *q < 0.0 {
+                return None;
+            }
+            continue;
+        }
+
+        let t = q / p;
+        if *p < 0.0 {
+            t_enter = t_enter.max(t);
+        } else {
+            t_exit = t_exit.min(t);
+        }
+
+        if t_enter > t_exit {
+            return None;
+        }
+    }
+
+    return Some((x0 + t_enter * dx, y0 + t_enter * dy, x0 + t_exit * dx, y0 + t_exit * dy));
+}
+
+impl View {
+    pub fn new(camera: Camera, renderer: Renderer, viewport: Viewport) -> View {
+        return View {camera: camera, renderer: renderer, viewport: viewport, enabled: true, border: false};
+    }
+
+    pub fn render<T: BatchProject>(&mut self, objects: &[GameObject], output_aspect: f64, output: &mut Vec::<T>) -> FrameStats {
+        if !self.enabled {
+            return FrameStats::default();
+        }
+
+        self.renderer.update_aspect_ratio(self.viewport.aspect_ratio(output_aspect));
+        let (lines, stats) = self.renderer.render_frame_stats::<T>(objects, &self.camera);
+
+        self.viewport.compose(&lines, output);
+        if self.border {
+            self.viewport.border(output);
+        }
+        return stats;
+    }
+}
+
+// Extra views keyed by name, drawn in the order they were added
+pub struct ViewSet {
+    pub views: Vec<(String, View)>,
+}
+
+impl ViewSet {
+    pub fn default() -> ViewSet {
+        return ViewSet {views: Vec::new()};
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut View> {
+        return self.views.iter_mut().find(|(view_name, _)| view_name == name).map(|(_, view)| view);
+    }
+
+    // Replaces any view with the same name
+    pub fn insert(&mut self, name: &str, view: View) {
+        match self.get_mut(name) {
+            Some(existing) => *existing = view,
+            None => self.views.push((String::from(name), view)),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        let count = self.views.len();
+        self.views.retain(|(view_name, _)| view_name != name);
+        return self.views.len() != count;
+    }
+
+    pub fn render<T: BatchProject>(&mut self, objects: &[GameObject], output_aspect: f64, output: &mut Vec::<T>) -> FrameStats {
+        let mut stats = FrameStats::default();
+        for (_, view) in self.views.iter_mut() {
+            stats.add(&view.render(objects, output_aspect, output));
+        }
+        return stats;
+    }
+}
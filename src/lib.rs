@@ -1,21 +1,13 @@
+// The engine sticks to explicit returns, field-by-field struct literals and inherent `default()` constructors
+#![allow(clippy::needless_return, clippy::redundant_field_names, clippy::should_implement_trait)]
+
 mod utils;
-mod eng;
+pub mod eng;
 
 extern crate wasm_bindgen;
 
 use wasm_bindgen::prelude::*;
-use eng::euler::{Vec3, Vec4, Mat3, Mat4};
-
-
-
-extern crate web_sys;
-
-// A macro to provide `println!(..)`-style syntax for `console.log` logging.
-macro_rules! log {
-    ( $( $t:tt )* ) => {
-        web_sys::console::log_1(&format!( $( $t )* ).into());
-    }
-}
+use log::{info, warn};
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
@@ -27,58 +19,1053 @@ static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 #[wasm_bindgen]
 pub struct GlobalWrapper {
     lines: Vec<f64>,
+    line_eyes: Vec<u8>,
+    contacts: Vec<f64>,
     renderer: eng::renderer::Renderer,
     camera: eng::renderer::Camera,
-    objects: Vec<eng::renderer::GameObject>
+    objects: Vec<eng::renderer::GameObject>,
+    collisions: eng::collision::CollisionDetector,
+    physics: eng::physics::PhysicsWorld,
+    animator: eng::animation::Animator,
+    rigs: Vec<eng::skeleton::Rig>,
+    morphs: Vec<eng::morph::Morph>,
+    hud: eng::hud::Hud,
+    main_viewport: eng::viewport::Viewport,
+    views: eng::viewport::ViewSet,
+    stereo: eng::stereo::StereoSettings,
+    beam: eng::beam::BeamSettings,
+    beam_path: Vec<f64>,
+    beam_stats: Option<eng::beam::BeamStats>,
+    scope: eng::scope::ScopeSettings,
+    scope_samples: Vec<f32>,
+    ilda: eng::ilda::IldaSettings,
+    ilda_recording: Option<eng::ilda::IldaRecording>,
+    single_precision: bool,
+    lines_f32: Vec<f32>,
+    frame_stats: eng::stats::FrameStats,
+    input: eng::input::InputMap
+}
+
+#[wasm_bindgen]
+pub struct PickResult {
+    pub object_id: usize,
+    pub distance: f64,
 }
 
 #[wasm_bindgen]
 impl GlobalWrapper {
+    // The frame as a beam path for the XY outputs, optimized if beam optimization is on
+    fn output_path(&self) -> Vec<f64> {
+        if self.beam.enabled {
+            return self.beam_path.clone();
+        }
+        return eng::beam::unoptimized_path(&self.lines_f64());
+    }
+
     pub fn new() -> GlobalWrapper { 
+        utils::set_panic_hook();
+        utils::init_logging();
+
         let renderer = eng::renderer::Renderer::default();
         let camera  = eng::renderer::Camera::default();
 
         return GlobalWrapper {
             lines: Vec::new(),
+            line_eyes: Vec::new(),
+            contacts: Vec::new(),
             renderer: renderer,
             camera: camera,
-            objects: Vec::<eng::renderer::GameObject>::new()
+            objects: Vec::<eng::renderer::GameObject>::new(),
+            collisions: eng::collision::CollisionDetector::default(),
+            physics: eng::physics::PhysicsWorld::default(),
+            animator: eng::animation::Animator::default(),
+            rigs: Vec::<eng::skeleton::Rig>::new(),
+            morphs: Vec::<eng::morph::Morph>::new(),
+            hud: eng::hud::Hud::default(),
+            main_viewport: eng::viewport::Viewport::default(),
+            views: eng::viewport::ViewSet::default(),
+            stereo: eng::stereo::StereoSettings::default(),
+            beam: eng::beam::BeamSettings::default(),
+            beam_path: Vec::new(),
+            beam_stats: None,
+            scope: eng::scope::ScopeSettings::default(),
+            scope_samples: Vec::new(),
+            ilda: eng::ilda::IldaSettings::default(),
+            ilda_recording: None,
+            single_precision: false,
+            lines_f32: Vec::new(),
+            frame_stats: eng::stats::FrameStats::default(),
+            input: eng::input::InputMap::default()
         }        
     }
     
-    pub fn add_shape(& mut self, object_name: String, side_length: f64, x_r: f64, y_r: f64, z_r: f64) {
-        let mut shape: eng::renderer::GameObject = eng::renderer::GameObject::default();
-        
-        match object_name.as_str() {
-            "cube" => shape = eng::renderer::make_cube(side_length),
-            "tesseract" => shape = eng::renderer::make_tesseract(side_length),
-            "skeleton_1" => shape = eng::renderer::make_skeleton1(side_length),
-            _ => println!("We have a problem"),
-        }
+    // Returns the object ID, which is its index in `objects` and what `pick` reports. Throws
+    // for an unknown shape name.
+    pub fn add_shape(& mut self, object_name: String, side_length: f64, x_r: f64, y_r: f64, z_r: f64) -> Result<usize, JsValue> {
+        let mut shape: eng::renderer::GameObject = eng::renderer::make_shape(&object_name, side_length)
+            .inspect_err(|error| warn!("add_shape: {}", error))?;
 
         shape.angular_velocity = eng::euler::x_rotation_matrix(x_r) * shape.angular_velocity;
         shape.angular_velocity = eng::euler::y_rotation_matrix(y_r) * shape.angular_velocity;
         shape.angular_velocity = eng::euler::z_rotation_matrix(z_r) * shape.angular_velocity;
         
-        self.objects.push(shape);
+        let object_id = self.add_object(shape).inspect_err(|error| warn!("add_shape: {}", error))?;
+
+        // Its five points are joints, so they can be posed like a stick figure's
+        if object_name == "skeleton_1" {
+            self.rigs.push(eng::skeleton::Rig::new(object_id, eng::skeleton::skeleton1(side_length)));
+        }
+        return Ok(object_id);
+    }
+
+    // Adds the wireframe from the text of an OBJ file and returns its object ID. Throws with the
+    // offending line if the file cannot be read.
+    pub fn add_shape_from_obj(&mut self, source: String) -> Result<usize, JsValue> {
+        let shape = eng::renderer::GameObject::from_obj(&source)
+            .inspect_err(|error| warn!("add_shape_from_obj: {}", error))?;
+        let (point_count, line_count) = (shape.points.len(), shape.connections.len() / 2);
+
+        let object_id = self.add_object(shape).inspect_err(|error| warn!("add_shape_from_obj: {}", error))?;
+        info!("Loaded OBJ wireframe with {} points and {} lines", point_count, line_count);
+        return Ok(object_id);
+    }
+
+    // Every object added from outside goes through here, so malformed wireframes are rejected
+    // up front instead of being skipped while rendering
+    fn add_object(&mut self, object: eng::renderer::GameObject) -> Result<usize, eng::Error> {
+        object.validate()?;
+
+        self.objects.push(object);
+        return Ok(self.objects.len() - 1);
     }
 
     pub fn update_aspect_ratio(&mut self, value: f64) {
         self.renderer.update_aspect_ratio(value);
     }
 
-    pub fn get_lines(&self) -> *const f64{
+    // A single precision frame is only widened into this buffer when it is asked for
+    pub fn get_lines(&mut self) -> *const f64{
+        if self.single_precision {
+            self.lines.clear();
+            self.lines.extend(self.lines_f32.iter().map(|&value| value as f64));
+        }
         return self.lines.as_ptr();
     }
     pub fn get_lines_length(&self) -> usize {
+        if self.single_precision {
+            return self.lines_f32.len();
+        }
         return self.lines.len();
     }
 
+    // "f32" renders every 3D pass in single precision straight into `get_lines_f32`, half the size
+    // of `get_lines`; "f64" goes back to double precision only
+    pub fn set_line_precision(&mut self, precision: String) -> bool {
+        let single_precision = match precision.as_str() {
+            "f32" => true,
+            "f64" => false,
+            _ => return false,
+        };
+
+        // Carries the last frame over so it can still be read until the next one is rendered
+        if single_precision && !self.single_precision {
+            self.lines_f32 = self.lines.iter().map(|&value| value as f32).collect();
+        } else if !single_precision && self.single_precision {
+            self.lines = self.lines_f64().into_owned();
+            self.lines_f32.clear();
+        }
+
+        self.single_precision = single_precision;
+        return true;
+    }
+
+    pub fn get_lines_f32(&self) -> *const f32 {
+        return self.lines_f32.as_ptr();
+    }
+    pub fn get_lines_f32_length(&self) -> usize {
+        return self.lines_f32.len();
+    }
+
+    // One tag per line (every four values of `get_lines`): 0 for both eyes, 1 left, 2 right
+    pub fn get_line_eyes(&self) -> *const u8 {
+        return self.line_eyes.as_ptr();
+    }
+    pub fn get_line_eyes_length(&self) -> usize {
+        return self.line_eyes.len();
+    }
+
+    // Mode is "off", "anaglyph" or "side_by_side". The eyes sit `interocular` apart along the
+    // camera's x axis and agree on the position of points `convergence` away from the camera.
+    pub fn set_stereo(&mut self, mode: String, interocular: f64, convergence: f64) -> bool {
+        return match eng::stereo::StereoMode::parse(&mode) {
+            Some(mode) => {
+                self.stereo = eng::stereo::StereoSettings::new(mode, interocular, convergence);
+                true
+            },
+            None => false,
+        };
+    }
+
+    // Each contact is `CONTACT_STRIDE` values: a, b, point xyz, normal xyz, depth
+    pub fn get_contacts(&self) -> *const f64 {
+        return self.contacts.as_ptr();
+    }
+    pub fn get_contacts_length(&self) -> usize {
+        return self.contacts.len();
+    }
+
+    // One of "off", "sphere", "aabb" or "edges"; returns false for anything else. Detection is
+    // off until this is called. Every object is tested, but only rigid bodies are pushed apart.
+    pub fn set_collision_mode(&mut self, mode: String, edge_tolerance: f64) -> bool {
+        let narrow_phase = match mode.as_str() {
+            "off" => eng::collision::NarrowPhase::Off,
+            "sphere" => eng::collision::NarrowPhase::Sphere,
+            "aabb" => eng::collision::NarrowPhase::Aabb,
+            "edges" => eng::collision::NarrowPhase::Edges,
+            _ => return false,
+        };
+
+        self.collisions = eng::collision::CollisionDetector::new(narrow_phase, edge_tolerance);
+        return true;
+    }
+
+    // Screen coordinates are in the same [-1, 1] space as the line buffer
+    pub fn pick(&self, screen_x: f64, screen_y: f64) -> Option<PickResult> {
+        let viewport = self.main_viewport;
+        let local_x = 2.0 * (screen_x - viewport.left) / viewport.width - 1.0;
+        let local_y = 2.0 * (screen_y - viewport.bottom) / viewport.height - 1.0;
+
+        let ray = self.main_renderer().screen_to_ray(&self.camera, local_x, local_y)?;
+
+        return eng::picking::pick(&self.objects, &ray).map(|hit| PickResult {
+            object_id: hit.object_id,
+            distance: hit.distance,
+        });
+    }
+
+    // A mass of zero makes the object immovable; returns false for an unknown object ID
+    pub fn add_rigid_body(&mut self, object_id: usize, mass: f64, restitution: f64, linear_damping: f64, angular_damping: f64) -> bool {
+        return match self.objects.get_mut(object_id) {
+            Some(object) => {
+                object.body = Some(eng::physics::RigidBody::for_object(object, mass, restitution, linear_damping, angular_damping));
+                true
+            },
+            None => false,
+        };
+    }
+
+    pub fn apply_force(&mut self, object_id: usize, x: f64, y: f64, z: f64) {
+        if let Some(body) = self.objects.get_mut(object_id).and_then(|object| object.body.as_mut()) {
+            body.apply_force(eng::euler::Vec3::new(x, y, z));
+        }
+    }
+
+    pub fn apply_torque(&mut self, object_id: usize, x: f64, y: f64, z: f64) {
+        if let Some(body) = self.objects.get_mut(object_id).and_then(|object| object.body.as_mut()) {
+            body.apply_torque(eng::euler::Vec3::new(x, y, z));
+        }
+    }
+
+    pub fn apply_impulse(&mut self, object_id: usize, x: f64, y: f64, z: f64) {
+        if let Some(object) = self.objects.get_mut(object_id) {
+            self.physics.apply_impulse(object, eng::euler::Vec3::new(x, y, z), eng::euler::Vec3::default());
+        }
+    }
+
+    pub fn set_gravity(&mut self, x: f64, y: f64, z: f64) {
+        self.physics.gravity = eng::euler::Vec3::new(x, y, z);
+    }
+
+    // One of "euler" or "verlet"; returns false for anything else
+    pub fn set_integrator(&mut self, name: String) -> bool {
+        self.physics.integrator = match name.as_str() {
+            "euler" => eng::physics::Integrator::SemiImplicitEuler,
+            "verlet" => eng::physics::Integrator::Verlet,
+            _ => return false,
+        };
+        return true;
+    }
+
+    // Keyframe times are in ticks of `render_loop`, starting from 0 on the first frame. Easing is one
+    // of "step", "linear", "ease_in", "ease_out", "ease_in_out" or "bezier(x1, y1, x2, y2)"; each
+    // returns false if it isn't recognized or the object does not exist.
+    pub fn add_position_keyframe(&mut self, object_id: usize, time: f64, x: f64, y: f64, z: f64, easing: String) -> bool {
+        if object_id >= self.objects.len() {
+            return false;
+        }
+        return match parse_easing(&easing) {
+            Some(easing) => {
+                self.animator.object(object_id).position.insert(time, eng::euler::Vec3::new(x, y, z), easing);
+                true
+            },
+            None => false,
+        };
+    }
+
+    pub fn add_orientation_keyframe(&mut self, object_id: usize, time: f64, x_r: f64, y_r: f64, z_r: f64, easing: String) -> bool {
+        if object_id >= self.objects.len() {
+            return false;
+        }
+        return match parse_easing(&easing) {
+            Some(easing) => {
+                self.animator.object(object_id).orientation.insert(time, eng::euler::Quat::from_euler_angles(x_r, y_r, z_r), easing);
+                true
+            },
+            None => false,
+        };
+    }
+
+    pub fn add_scale_keyframe(&mut self, object_id: usize, time: f64, x: f64, y: f64, z: f64, easing: String) -> bool {
+        if object_id >= self.objects.len() {
+            return false;
+        }
+        return match parse_easing(&easing) {
+            Some(easing) => {
+                self.animator.object(object_id).scale.insert(time, eng::euler::Vec3::new(x, y, z), easing);
+                true
+            },
+            None => false,
+        };
+    }
+
+    pub fn add_camera_position_keyframe(&mut self, time: f64, x: f64, y: f64, z: f64, easing: String) -> bool {
+        return match parse_easing(&easing) {
+            Some(easing) => {
+                self.animator.camera.position.insert(time, eng::euler::Vec3::new(x, y, z), easing);
+                true
+            },
+            None => false,
+        };
+    }
+
+    pub fn add_camera_orientation_keyframe(&mut self, time: f64, x_r: f64, y_r: f64, z_r: f64, easing: String) -> bool {
+        return match parse_easing(&easing) {
+            Some(easing) => {
+                self.animator.camera.orientation.insert(time, eng::euler::Quat::from_euler_angles(x_r, y_r, z_r), easing);
+                true
+            },
+            None => false,
+        };
+    }
+
+    pub fn add_camera_fov_keyframe(&mut self, time: f64, fov: f64, easing: String) -> bool {
+        return match parse_easing(&easing) {
+            Some(easing) => {
+                self.animator.camera.fov.insert(time, fov, easing);
+                true
+            },
+            None => false,
+        };
+    }
+
+    // One of "once", "loop" or "ping_pong". Tracks play once unless told otherwise.
+    pub fn set_object_loop_mode(&mut self, object_id: usize, mode: String) -> bool {
+        if object_id >= self.objects.len() {
+            return false;
+        }
+        return match eng::animation::LoopMode::parse(&mode) {
+            Some(loop_mode) => {
+                self.animator.object(object_id).set_loop_mode(loop_mode);
+                true
+            },
+            None => false,
+        };
+    }
+
+    pub fn set_camera_loop_mode(&mut self, mode: String) -> bool {
+        return match eng::animation::LoopMode::parse(&mode) {
+            Some(loop_mode) => {
+                self.animator.camera.set_loop_mode(loop_mode);
+                true
+            },
+            None => false,
+        };
+    }
+
+    pub fn play_animation(&mut self) {
+        self.animator.playing = true;
+    }
+
+    pub fn pause_animation(&mut self) {
+        self.animator.playing = false;
+    }
+
+    pub fn seek_animation(&mut self, time: f64) {
+        self.animator.seek(time);
+    }
+
+    pub fn get_animation_time(&self) -> f64 {
+        return self.animator.time;
+    }
+
+    // Adds a stick figure object driven by a skeleton and returns its object ID
+    pub fn add_stick_figure(&mut self, size: f64) -> Result<usize, JsValue> {
+        let skeleton = eng::skeleton::make_stick_figure(size);
+        let object_id = self.add_object(skeleton.to_game_object())
+            .inspect_err(|error| warn!("add_stick_figure: {}", error))?;
+
+        self.rigs.push(eng::skeleton::Rig::new(object_id, skeleton));
+        return Ok(object_id);
+    }
+
+    pub fn set_bone_rotation(&mut self, object_id: usize, bone_name: String, x_r: f64, y_r: f64, z_r: f64) -> bool {
+        return match self.rigs.iter_mut().find(|rig| rig.object_id == object_id) {
+            Some(rig) => rig.skeleton.set_rotation(&bone_name, eng::euler::Quat::from_euler_angles(x_r, y_r, z_r)),
+            None => false,
+        };
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_bone_keyframe(&mut self, object_id: usize, bone_name: String, time: f64, x_r: f64, y_r: f64, z_r: f64, easing: String) -> bool {
+        let easing = match parse_easing(&easing) {
+            Some(easing) => easing,
+            None => return false,
+        };
+
+        return match self.rigs.iter_mut().find(|rig| rig.object_id == object_id) {
+            Some(rig) => rig.add_keyframe(&bone_name, time, eng::euler::Quat::from_euler_angles(x_r, y_r, z_r), easing),
+            None => false,
+        };
+    }
+
+    pub fn set_rig_loop_mode(&mut self, object_id: usize, mode: String) -> bool {
+        let loop_mode = match eng::animation::LoopMode::parse(&mode) {
+            Some(loop_mode) => loop_mode,
+            None => return false,
+        };
+
+        return match self.rigs.iter_mut().find(|rig| rig.object_id == object_id) {
+            Some(rig) => {
+                rig.set_loop_mode(loop_mode);
+                true
+            },
+            None => false,
+        };
+    }
+
+    // Target and pole are in the figure's own (model) space
+    #[allow(clippy::too_many_arguments)]
+    pub fn solve_ik(&mut self, object_id: usize, end_bone: String, target_x: f64, target_y: f64, target_z: f64, pole_x: f64, pole_y: f64, pole_z: f64) -> bool {
+        return match self.rigs.iter_mut().find(|rig| rig.object_id == object_id) {
+            Some(rig) => rig.skeleton.solve_two_bone_ik(
+                &end_bone,
+                eng::euler::Vec3::new(target_x, target_y, target_z),
+                eng::euler::Vec3::new(pole_x, pole_y, pole_z)),
+            None => false,
+        };
+    }
+
+    // Adds a shape (by `add_shape` name) as a morph target of an object and returns the target
+    // index. Once any target differs in topology from the object, the object and all of its
+    // targets are resampled together into at least `segments` matching segments. Throws for an
+    // object posed by a skeleton, whose rig rewrites its points and connections every frame.
+    pub fn add_morph_target(&mut self, object_id: usize, object_name: String, side_length: f64, segments: usize) -> Result<usize, JsValue> {
+        if self.rigs.iter().any(|rig| rig.object_id == object_id) {
+            let error = eng::Error::RiggedObject(object_id);
+            warn!("add_morph_target: {}", error);
+            return Err(error.into());
+        }
+        let target = eng::renderer::make_shape(&object_name, side_length)
+            .and_then(|target| target.validate().map(|_| target))
+            .inspect_err(|error| warn!("add_morph_target: {}", error))?;
+        let object = match self.objects.get_mut(object_id) {
+            Some(object) => object,
+            None => return Err(eng::Error::UnknownObject(object_id).into()),
+        };
+
+        // A new morph is only kept once its first target is in, so a failure leaves nothing behind
+        let target_idx = match self.morphs.iter_mut().find(|morph| morph.object_id == object_id) {
+            Some(morph) => morph.add_shape(object, target, segments),
+            None => {
+                let mut morph = eng::morph::Morph::new(object_id, object);
+                let target_idx = morph.add_shape(object, target, segments);
+                if target_idx.is_some() {
+                    self.morphs.push(morph);
+                }
+                target_idx
+            },
+        };
+
+        return target_idx.ok_or_else(|| eng::Error::NoSegments.into());
+    }
+
+    pub fn set_morph_weight(&mut self, object_id: usize, target: usize, weight: f64) -> bool {
+        return match self.morphs.iter_mut().find(|morph| morph.object_id == object_id) {
+            Some(morph) => morph.set_weight(target, weight),
+            None => false,
+        };
+    }
+
+    // Adds overlay text to the HUD anchored at (x, y) and returns its HUD element ID. `size` is the
+    // height of a capital letter in HUD units; alignment is "left", "center" or "right" and
+    // vertical alignment "top", "middle" or "bottom".
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_text(&mut self, text: String, x: f64, y: f64, size: f64, align: String, vertical_align: String) -> Option<usize> {
+        let style = text_style(size, &align, &vertical_align)?;
+
+        return Some(self.hud.add(eng::hud::HudElement::Text(eng::font::OverlayText::new(&text, x, y, style))));
+    }
+
+    pub fn set_text(&mut self, text_id: usize, text: String) -> bool {
+        return match self.hud.get_mut(text_id) {
+            Some(eng::hud::HudItem {element: eng::hud::HudElement::Text(overlay), ..}) => {
+                overlay.text = text;
+                true
+            },
+            _ => false,
+        };
+    }
+
+    pub fn set_text_visible(&mut self, text_id: usize, visible: bool) -> bool {
+        return self.hud_set_visible(text_id, visible);
+    }
+
+    // The HUD is drawn after the 3D scene. Its coordinates span left..right and bottom..top
+    // across the output, which is [-1, 1] on both axes until changed here.
+    pub fn hud_set_coordinates(&mut self, left: f64, right: f64, bottom: f64, top: f64) -> bool {
+        return self.hud.set_coordinates(left, right, bottom, top);
+    }
+
+    // Scales the HUD's x axis by the aspect ratio so circles and squares keep their shape
+    pub fn hud_set_aspect_correction(&mut self, enabled: bool) {
+        self.hud.aspect_correction = enabled;
+    }
+
+    pub fn hud_set_layer_visible(&mut self, visible: bool) {
+        self.hud.visible = visible;
+    }
+
+    pub fn hud_add_line(&mut self, x0: f64, y0: f64, x1: f64, y1: f64) -> usize {
+        return self.hud.add(eng::hud::HudElement::Line(x0, y0, x1, y1));
+    }
+
+    pub fn hud_add_rect(&mut self, left: f64, bottom: f64, width: f64, height: f64) -> usize {
+        return self.hud.add(eng::hud::HudElement::Rect(left, bottom, width, height));
+    }
+
+    pub fn hud_add_circle(&mut self, center_x: f64, center_y: f64, radius: f64, segments: usize) -> usize {
+        return self.hud.add(eng::hud::HudElement::Circle(center_x, center_y, radius, segments));
+    }
+
+    // `points` is a flat x0, y0, x1, y1, ... list
+    pub fn hud_add_polyline(&mut self, points: Vec<f64>, closed: bool) -> usize {
+        let points = points.chunks_exact(2).map(|pair| (pair[0], pair[1])).collect();
+        return self.hud.add(eng::hud::HudElement::Polyline(points, closed));
+    }
+
+    pub fn hud_set_visible(&mut self, element_id: usize, visible: bool) -> bool {
+        return match self.hud.get_mut(element_id) {
+            Some(item) => {
+                item.visible = visible;
+                true
+            },
+            None => false,
+        };
+    }
+
+    pub fn hud_remove(&mut self, element_id: usize) -> bool {
+        return self.hud.remove(element_id);
+    }
+
+    pub fn hud_clear(&mut self) {
+        self.hud.clear();
+    }
+
+    // Adds the text as a 3D object and returns its object ID
+    pub fn add_text_object(&mut self, text: String, size: f64, align: String, vertical_align: String) -> Result<usize, JsValue> {
+        let style = match text_style(size, &align, &vertical_align) {
+            Some(style) => style,
+            None => return Err(eng::Error::UnknownAlignment(format!("{} {}", align, vertical_align)).into()),
+        };
+
+        let object_id = self.add_object(eng::font::make_text(&text, &style))
+            .inspect_err(|error| warn!("add_text_object: {}", error))?;
+        return Ok(object_id);
+    }
+
+    // Viewports are rectangles of the [-1, 1] output. The main camera fills the whole output
+    // unless it is given a smaller viewport here, e.g. for split screen.
+    pub fn set_main_viewport(&mut self, left: f64, bottom: f64, width: f64, height: f64) {
+        self.main_viewport = eng::viewport::Viewport::new(left, bottom, width, height);
+    }
+
+    // Adds or replaces a named view, starting from a copy of the main camera and default
+    // projection settings. Views are drawn after the main camera in the order they were added.
+    pub fn add_view(&mut self, name: String, left: f64, bottom: f64, width: f64, height: f64) {
+        let view = eng::viewport::View::new(
+            self.camera,
+            eng::renderer::Renderer::default(),
+            eng::viewport::Viewport::new(left, bottom, width, height));
+
+        self.views.insert(&name, view);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_view_camera(&mut self, name: String, x: f64, y: f64, z: f64, x_r: f64, y_r: f64, z_r: f64) -> bool {
+        return match self.views.get_mut(&name) {
+            Some(view) => {
+                view.camera.position = eng::euler::Vec3::new(x, y, z);
+                view.camera.orientation = eng::euler::Quat::from_euler_angles(x_r, y_r, z_r).to_matrix();
+                true
+            },
+            None => false,
+        };
+    }
+
+    pub fn set_view_fov(&mut self, name: String, fov: f64) -> bool {
+        return match self.views.get_mut(&name) {
+            Some(view) => {
+                view.renderer.set_fov(fov);
+                true
+            },
+            None => false,
+        };
+    }
+
+    pub fn set_view_enabled(&mut self, name: String, enabled: bool) -> bool {
+        return match self.views.get_mut(&name) {
+            Some(view) => {
+                view.enabled = enabled;
+                true
+            },
+            None => false,
+        };
+    }
+
+    pub fn set_view_border(&mut self, name: String, border: bool) -> bool {
+        return match self.views.get_mut(&name) {
+            Some(view) => {
+                view.border = border;
+                true
+            },
+            None => false,
+        };
+    }
+
+    pub fn remove_view(&mut self, name: String) -> bool {
+        return self.views.remove(&name);
+    }
+
+    // When enabled, every frame is also turned into a single beam path for XY monitors: segments
+    // sharing endpoints within `join_tolerance` are chained and the chains ordered to keep blank
+    // moves short, refined by up to `two_opt_passes` passes of 2-opt
+    pub fn set_beam_optimization(&mut self, enabled: bool, join_tolerance: f64, two_opt_passes: usize) {
+        self.beam.enabled = enabled;
+        self.beam.join_tolerance = join_tolerance;
+        self.beam.two_opt_passes = two_opt_passes;
+
+        if !enabled {
+            self.beam_path.clear();
+            self.beam_stats = None;
+        }
+    }
+
+    // Each point is `BEAM_POINT_STRIDE` values: x, y, then 1 to draw to it or 0 to move blanked
+    pub fn get_beam_path(&self) -> *const f64 {
+        return self.beam_path.as_ptr();
+    }
+    pub fn get_beam_path_length(&self) -> usize {
+        return self.beam_path.len();
+    }
+
+    pub fn get_beam_draw_distance(&self) -> f64 {
+        return self.beam_stats.map_or(0.0, |stats| stats.draw_distance);
+    }
+
+    pub fn get_beam_blank_distance(&self) -> f64 {
+        return self.beam_stats.map_or(0.0, |stats| stats.blank_distance);
+    }
+
+    // Raw input, forwarded from JS event handlers as it happens. `code` is `KeyboardEvent.code`.
+    pub fn key_down(&mut self, code: String) {
+        self.input.set_button(eng::input::Source::Key(code), true);
+    }
+    pub fn key_up(&mut self, code: String) {
+        self.input.set_button(eng::input::Source::Key(code), false);
+    }
+    pub fn set_gamepad_button(&mut self, pad: usize, button: usize, down: bool) {
+        self.input.set_button(eng::input::Source::GamepadButton {pad: pad, button: button}, down);
+    }
+    pub fn set_gamepad_axis(&mut self, pad: usize, axis: usize, value: f64) {
+        self.input.set_axis(eng::input::Source::GamepadAxis {pad: pad, axis: axis}, value);
+    }
+    pub fn add_spinner_delta(&mut self, spinner: usize, delta: f64) {
+        self.input.add_delta(eng::input::Source::Spinner(spinner), delta);
+    }
+    // A trackball reports through spinners 2 * `trackball` (x) and 2 * `trackball` + 1 (y)
+    pub fn add_trackball_delta(&mut self, trackball: usize, dx: f64, dy: f64) {
+        self.input.add_delta(eng::input::Source::Spinner(2 * trackball), dx);
+        self.input.add_delta(eng::input::Source::Spinner(2 * trackball + 1), dy);
+    }
+    pub fn set_coin_switch(&mut self, slot: usize, closed: bool) {
+        self.input.set_button(eng::input::Source::Coin(slot), closed);
+    }
+
+    // `source` is "key:<code>", "button:<pad>:<button>", "axis:<pad>:<axis>", "spinner:<index>" or
+    // "coin:<slot>". Several sources can feed one action; `scale` weights and signs each of them.
+    pub fn bind_input(&mut self, source: String, action: String, scale: f64) -> bool {
+        return match eng::input::Source::parse(&source) {
+            Some(source) => {
+                self.input.bind(source, &action, scale);
+                true
+            },
+            None => false,
+        };
+    }
+
+    pub fn unbind_input(&mut self, source: String) -> bool {
+        return eng::input::Source::parse(&source).is_some_and(|source| self.input.unbind(&source));
+    }
+
+    pub fn set_axis_deadzone(&mut self, deadzone: f64) {
+        self.input.axis_deadzone = deadzone;
+    }
+
+    // Action state as of the current frame; unknown actions read as idle
+    pub fn get_action_value(&self, action: String) -> f64 {
+        return self.input.action(&action).value;
+    }
+    pub fn is_action_down(&self, action: String) -> bool {
+        return self.input.action(&action).down;
+    }
+    pub fn was_action_pressed(&self, action: String) -> bool {
+        return self.input.action(&action).pressed;
+    }
+    pub fn was_action_released(&self, action: String) -> bool {
+        return self.input.action(&action).released;
+    }
+
+    // Each frame, moves or turns the camera by `rate` times the action's value, in its own axes.
+    // `motion` is "move_x", "move_y", "move_z", "yaw", "pitch" or "roll". Camera animation
+    // tracks are applied afterwards and win over drives.
+    pub fn drive_camera(&mut self, motion: String, action: String, rate: f64) -> bool {
+        return self.add_drive(eng::input::DriveTarget::Camera, &motion, action, rate);
+    }
+
+    // Like `drive_camera`, for the object `object_id`
+    pub fn drive_object(&mut self, object_id: usize, motion: String, action: String, rate: f64) -> bool {
+        if object_id >= self.objects.len() {
+            return false;
+        }
+        return self.add_drive(eng::input::DriveTarget::Object(object_id), &motion, action, rate);
+    }
+
+    pub fn clear_drives(&mut self) {
+        self.input.drives.clear();
+    }
+
+    // Counters for the last `render_loop`, summed over both eyes and every extra view. Times are
+    // in milliseconds; the cull, transform and emit stages together make up the 3D rendering.
+    pub fn get_frame_objects_rendered(&self) -> usize {
+        return self.frame_stats.objects_rendered;
+    }
+    pub fn get_frame_objects_culled(&self) -> usize {
+        return self.frame_stats.objects_culled;
+    }
+    pub fn get_frame_points_transformed(&self) -> usize {
+        return self.frame_stats.points_transformed;
+    }
+    pub fn get_frame_segments_emitted(&self) -> usize {
+        return self.frame_stats.segments_emitted;
+    }
+    pub fn get_frame_segments_offscreen(&self) -> usize {
+        return self.frame_stats.segments_offscreen;
+    }
+    pub fn get_frame_line_length(&self) -> f64 {
+        return self.frame_stats.line_length;
+    }
+    pub fn get_frame_cull_ms(&self) -> f64 {
+        return self.frame_stats.cull_ms;
+    }
+    pub fn get_frame_transform_ms(&self) -> f64 {
+        return self.frame_stats.transform_ms;
+    }
+    pub fn get_frame_emit_ms(&self) -> f64 {
+        return self.frame_stats.emit_ms;
+    }
+    pub fn get_frame_simulation_ms(&self) -> f64 {
+        return self.frame_stats.simulation_ms;
+    }
+    pub fn get_frame_output_ms(&self) -> f64 {
+        return self.frame_stats.output_ms;
+    }
+    pub fn get_frame_ms(&self) -> f64 {
+        return self.frame_stats.frame_ms;
+    }
+
+    // When enabled, every frame is also sampled as a stereo signal for an oscilloscope in XY mode,
+    // following the optimized beam path if there is one. `blanking` is "trace", "jump" or "dwell".
+    pub fn set_scope_output(&mut self, enabled: bool, sample_rate: u32, points_per_unit: f64, blanking: String, dwell_samples: usize) -> bool {
+        let blanking = match eng::scope::Blanking::parse(&blanking) {
+            Some(blanking) => blanking,
+            None => return false,
+        };
+        if sample_rate == 0 || points_per_unit <= 0.0 {
+            return false;
+        }
+
+        self.scope.enabled = enabled;
+        self.scope.sample_rate = sample_rate;
+        self.scope.points_per_unit = points_per_unit;
+        self.scope.blanking = blanking;
+        self.scope.dwell_samples = dwell_samples;
+
+        if !enabled {
+            self.scope_samples.clear();
+        }
+        return true;
+    }
+
+    pub fn set_scope_amplitude(&mut self, amplitude: f64) {
+        self.scope.amplitude = amplitude;
+    }
+
+    // Interleaved left/right f32 samples for one pass over the frame, ready for an AudioBuffer
+    pub fn get_scope_samples(&self) -> *const f32 {
+        return self.scope_samples.as_ptr();
+    }
+    pub fn get_scope_samples_length(&self) -> usize {
+        return self.scope_samples.len();
+    }
+
+    // The current frame's samples as interleaved 16-bit PCM, without a WAV header
+    pub fn get_scope_pcm16(&self) -> Vec<i16> {
+        return eng::scope::to_pcm16(&self.scope_samples);
+    }
+
+    // The current frame's samples as a 16-bit stereo WAV file
+    pub fn get_scope_wav(&self) -> Vec<u8> {
+        return eng::scope::to_wav(&self.scope_samples, self.scope.sample_rate);
+    }
+
+    // `format` is the ILDA format code: 0 or 1 for indexed 3D or 2D, 4 or 5 for true-color 3D or 2D
+    pub fn set_ilda_format(&mut self, format: u8, points_per_unit: f64, blank_points: usize) -> bool {
+        let format = match eng::ilda::IldaFormat::from_code(format) {
+            Some(format) => format,
+            None => return false,
+        };
+        if points_per_unit <= 0.0 {
+            return false;
+        }
+
+        self.ilda.format = format;
+        self.ilda.points_per_unit = points_per_unit;
+        self.ilda.blank_points = blank_points;
+        return true;
+    }
+
+    pub fn set_ilda_color(&mut self, color_index: u8, red: u8, green: u8, blue: u8) {
+        self.ilda.color_index = color_index;
+        self.ilda.color = (red, green, blue);
+    }
+
+    // The last rendered frame as a single-frame ILDA file
+    pub fn get_ilda_frame(&self) -> Vec<u8> {
+        let frame = eng::ilda::frame_points(&self.output_path(), &self.ilda);
+        return eng::ilda::write_file(&[frame], &self.ilda);
+    }
+
+    // Starts collecting every rendered frame into an animation, dropping any earlier recording.
+    // It stops by itself at the 65535 frames a file can hold.
+    pub fn start_ilda_recording(&mut self) {
+        info!("ILDA recording started");
+        self.ilda_recording = Some(eng::ilda::IldaRecording::new(self.ilda));
+    }
+
+    pub fn stop_ilda_recording(&mut self) {
+        if let Some(recording) = &mut self.ilda_recording {
+            info!("ILDA recording stopped after {} frames", recording.frames.len());
+            recording.active = false;
+        }
+    }
+
+    pub fn is_ilda_recording(&self) -> bool {
+        return self.ilda_recording.as_ref().is_some_and(|recording| recording.active);
+    }
+
+    pub fn get_ilda_recording_frames(&self) -> usize {
+        return self.ilda_recording.as_ref().map_or(0, |recording| recording.frames.len());
+    }
+
+    // The recorded frames as a multi-frame ILDA file, empty if nothing was recorded
+    pub fn get_ilda_recording(&self) -> Vec<u8> {
+        return self.ilda_recording.as_ref().map_or(Vec::new(), |recording| recording.to_bytes());
+    }
+
     pub fn render_loop(&mut self) {
-        for object in &mut self.objects {
-            object.tick();
+        let frame_start = eng::stats::now_ms();
+        self.frame_stats = eng::stats::FrameStats::default();
+
+        self.input.update();
+        self.input.apply(&mut self.objects, &mut self.camera);
+
+        self.physics.step(&mut self.objects);
+
+        // Sample before advancing so keyframes at time 0 show on the first frame
+        self.animator.apply(&mut self.objects, &mut self.camera, &mut self.renderer);
+
+        for rig in &mut self.rigs {
+            rig.animate(self.animator.time);
+            rig.update(&mut self.objects);
+        }
+        self.animator.advance();
+
+        for morph in &self.morphs {
+            morph.update(&mut self.objects);
+        }
+
+        let contacts = self.collisions.detect(&self.objects);
+        self.physics.resolve(&mut self.objects, &contacts);
+
+        self.contacts.clear();
+        for contact in contacts {
+            contact.flatten_into(&mut self.contacts);
+        }
+        let render_start = eng::stats::now_ms();
+        self.frame_stats.simulation_ms = render_start - frame_start;
+
+        if self.single_precision {
+            let mut lines = std::mem::take(&mut self.lines_f32);
+            self.render_scene(&mut lines);
+            self.lines_f32 = lines;
+        } else {
+            let mut lines = std::mem::take(&mut self.lines);
+            self.render_scene(&mut lines);
+            self.lines = lines;
+        }
+        let output_start = eng::stats::now_ms();
+
+        if self.beam.enabled {
+            let (path, stats) = eng::beam::optimize(&self.lines_f64(), &self.beam);
+            self.beam_path = path;
+            self.beam_stats = Some(stats);
+        }
+
+        let recording = self.ilda_recording.as_ref().is_some_and(|recording| recording.active);
+
+        if self.scope.enabled || recording {
+            let path = self.output_path();
+
+            if self.scope.enabled {
+                self.scope_samples = eng::scope::synthesize(&path, &self.scope);
+            }
+            if let Some(recording) = self.ilda_recording.as_mut().filter(|recording| recording.active) {
+                recording.add_frame(&path);
+            }
+        }
+
+        let frame_end = eng::stats::now_ms();
+        self.frame_stats.output_ms = frame_end - output_start;
+        self.frame_stats.frame_ms = frame_end - frame_start;
+    }
+}
+
+impl GlobalWrapper {
+    // The main renderer adjusted for the shape of the main viewport
+    fn main_renderer(&self) -> eng::renderer::Renderer {
+        let mut renderer = self.renderer;
+
+        if !self.main_viewport.is_full() {
+            renderer.update_aspect_ratio(self.main_viewport.aspect_ratio(self.renderer.ASP));
+        }
+        return renderer;
+    }
+
+    // Every pass of the frame, in the precision of `output`, with each line tagged by eye
+    fn render_scene<T: eng::batch::BatchProject>(&mut self, output: &mut Vec<T>) {
+        output.clear();
+        self.line_eyes.clear();
+
+        if self.stereo.mode == eng::stereo::StereoMode::Off {
+            let (renderer, camera, viewport) = (self.main_renderer(), self.camera, self.main_viewport);
+            self.render_view(renderer, &camera, &viewport, output);
+        } else {
+            self.render_eye(eng::stereo::Eye::Left, output);
+            self.render_eye(eng::stereo::Eye::Right, output);
         }
 
-        self.lines = self.renderer.render_frame(&self.objects, &self.camera);
+        let stats = self.views.render(&self.objects, self.renderer.ASP, output);
+        self.frame_stats.add(&stats);
+
+        output.extend(self.hud.lines(self.renderer.ASP).into_iter().map(T::from_f64));
+        self.tag_lines(output.len(), eng::stereo::Eye::Both);
+    }
+
+    fn render_eye<T: eng::batch::BatchProject>(&mut self, eye: eng::stereo::Eye, output: &mut Vec<T>) {
+        let viewport = self.stereo.eye_viewport(&self.main_viewport, eye);
+        let mut renderer = self.renderer;
+
+        if !viewport.is_full() {
+            renderer.update_aspect_ratio(viewport.aspect_ratio(self.renderer.ASP));
+        }
+
+        let renderer = self.stereo.eye_renderer(&renderer, eye);
+        let camera = self.stereo.eye_camera(&self.camera, eye);
+        self.render_view(renderer, &camera, &viewport, output);
+        self.tag_lines(output.len(), eye);
+    }
+
+    // The 3D pass in the precision of `output`, squeezed into `viewport`
+    fn render_view<T: eng::batch::BatchProject>(&mut self, mut renderer: eng::renderer::Renderer, camera: &eng::renderer::Camera,
+            viewport: &eng::viewport::Viewport, output: &mut Vec<T>) {
+        let (lines, stats) = renderer.render_frame_stats::<T>(&self.objects, camera);
+        self.frame_stats.add(&stats);
+
+        if !viewport.is_full() {
+            viewport.compose(&lines, output);
+        } else if output.is_empty() {
+            *output = lines;
+        } else {
+            output.extend(lines);
+        }
+    }
+
+    // The frame for the f64 consumers (beam, scope, ILDA), widened only in single precision
+    fn lines_f64(&self) -> std::borrow::Cow<'_, [f64]> {
+        if self.single_precision {
+            return std::borrow::Cow::Owned(self.lines_f32.iter().map(|&value| value as f64).collect());
+        }
+        return std::borrow::Cow::Borrowed(&self.lines);
+    }
+
+    fn add_drive(&mut self, target: eng::input::DriveTarget, motion: &str, action: String, rate: f64) -> bool {
+        return match eng::input::Motion::parse(motion) {
+            Some(motion) => {
+                self.input.drives.push(eng::input::Drive {target: target, motion: motion, action: action, rate: rate});
+                true
+            },
+            None => false,
+        };
+    }
+
+    // Tags every line added since the last call, given the number of values now in the buffer
+    fn tag_lines(&mut self, value_count: usize, eye: eng::stereo::Eye) {
+        let count = value_count / 4;
+        self.line_eyes.resize(count, eye as u8);
+    }
+}
+
+// The keyframe setters report a bad easing as false, with the reason in the log
+fn parse_easing(easing: &str) -> Option<eng::animation::Easing> {
+    return eng::animation::Easing::parse(easing).inspect_err(|error| warn!("{}", error)).ok();
+}
+
+fn text_style(size: f64, align: &str, vertical_align: &str) -> Option<eng::font::TextStyle> {
+    return Some(eng::font::TextStyle::new(
+        size,
+        eng::font::HorizontalAlign::parse(align)?,
+        eng::font::VerticalAlign::parse(vertical_align)?));
+}
+
+// Engine errors reach JS as exceptions holding the message string
+impl From<eng::Error> for JsValue {
+    fn from(error: eng::Error) -> JsValue {
+        return JsValue::from_str(&error.to_string());
+    }
+}
+
+impl Default for GlobalWrapper {
+    fn default() -> GlobalWrapper {
+        return GlobalWrapper::new();
     }
 }
@@ -8,3 +8,13 @@ pub fn set_panic_hook() {
     #[cfg(feature = "console_error_panic_hook")]
     console_error_panic_hook::set_once();
 }
+
+// Sends the `log` records of the engine to the browser console, or to stderr natively where
+// `RUST_LOG` picks the level. Later calls are ignored, since a logger can only be set once.
+pub fn init_logging() {
+    #[cfg(target_arch = "wasm32")]
+    let _ = console_log::init_with_level(log::Level::Info);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = env_logger::try_init();
+}
@@ -0,0 +1,203 @@
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+
+use std::f64::consts::FRAC_PI_2;
+
+use vector_demo::eng::animation::{Easing, LoopMode, ObjectAnimation, Track};
+use vector_demo::eng::euler::{Quat, Vec3};
+use vector_demo::eng::physics::{Integrator, PhysicsWorld, RigidBody};
+use vector_demo::eng::renderer::make_cube;
+use vector_demo::eng::Error;
+
+fn assert_near(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-6, "expected {}, got {}", expected, actual);
+}
+
+// 0 at t = 0 rising to 10 at t = 10
+fn ramp(loop_mode: LoopMode, easing: Easing) -> Track<f64> {
+    let mut track = Track::new(loop_mode);
+    track.insert(0.0, 0.0, easing);
+    track.insert(10.0, 10.0, Easing::Linear);
+    return track;
+}
+
+#[test]
+fn samples_hit_the_keyframes_exactly() {
+    let mut track = ramp(LoopMode::Once, Easing::EaseInOut);
+    track.insert(20.0, 4.0, Easing::Linear);
+
+    assert_near(track.sample(0.0).unwrap(), 0.0);
+    assert_near(track.sample(10.0).unwrap(), 10.0);
+    assert_near(track.sample(20.0).unwrap(), 4.0);
+}
+
+#[test]
+fn samples_between_keyframes_follow_the_easing_of_the_earlier_one() {
+    let mut track = ramp(LoopMode::Once, Easing::EaseIn);
+    track.insert(20.0, 0.0, Easing::Linear);
+
+    assert_near(track.sample(5.0).unwrap(), 1.25);
+    assert_near(track.sample(15.0).unwrap(), 5.0);
+}
+
+#[test]
+fn inserting_keeps_keyframes_sorted_and_replaces_same_time() {
+    let mut track = Track::new(LoopMode::Once);
+    track.insert(10.0, 10.0, Easing::Linear);
+    track.insert(0.0, 0.0, Easing::Linear);
+    track.insert(10.0, 20.0, Easing::Linear);
+
+    assert_eq!(track.keyframes.len(), 2);
+    assert_near(track.start(), 0.0);
+    assert_near(track.end(), 10.0);
+    assert_near(track.sample(5.0).unwrap(), 10.0);
+}
+
+#[test]
+fn empty_tracks_have_nothing_to_sample() {
+    let track = Track::<f64>::new(LoopMode::Loop);
+    assert!(track.sample(3.0).is_none());
+}
+
+#[test]
+fn once_holds_the_first_and_last_values() {
+    let track = ramp(LoopMode::Once, Easing::Linear);
+
+    assert_near(track.sample(-5.0).unwrap(), 0.0);
+    assert_near(track.sample(13.0).unwrap(), 10.0);
+    assert_near(track.sample(1000.0).unwrap(), 10.0);
+}
+
+#[test]
+fn loop_wraps_back_to_the_start() {
+    let track = ramp(LoopMode::Loop, Easing::Linear);
+
+    assert_near(track.sample(13.0).unwrap(), 3.0);
+    assert_near(track.sample(27.0).unwrap(), 7.0);
+    assert_near(track.sample(-2.0).unwrap(), 8.0);
+}
+
+#[test]
+fn ping_pong_plays_backwards_on_every_other_pass() {
+    let track = ramp(LoopMode::PingPong, Easing::Linear);
+
+    assert_near(track.sample(13.0).unwrap(), 7.0);
+    assert_near(track.sample(20.0).unwrap(), 0.0);
+    assert_near(track.sample(23.0).unwrap(), 3.0);
+}
+
+#[test]
+fn easings_start_at_zero_and_end_at_one() {
+    let easings = [Easing::Step, Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut, Easing::Bezier(0.42, 0.0, 0.58, 1.0)];
+
+    for easing in easings.iter() {
+        assert_near(easing.apply(0.0), 0.0);
+        assert_near(easing.apply(1.0), 1.0);
+    }
+}
+
+#[test]
+fn easings_shape_the_progress() {
+    assert_near(Easing::Step.apply(0.99), 0.0);
+    assert_near(Easing::Linear.apply(0.25), 0.25);
+    assert_near(Easing::EaseIn.apply(0.5), 0.125);
+    assert_near(Easing::EaseOut.apply(0.5), 0.875);
+    assert_near(Easing::EaseInOut.apply(0.25), 0.15625);
+    assert_near(Easing::EaseInOut.apply(0.5), 0.5);
+
+    // Progress outside [0, 1] is clamped
+    assert_near(Easing::Linear.apply(1.5), 1.0);
+    assert_near(Easing::EaseIn.apply(-0.5), 0.0);
+}
+
+#[test]
+fn bezier_matches_known_curves() {
+    // Control points on the diagonal give a straight line
+    assert_near(Easing::Bezier(0.25, 0.25, 0.75, 0.75).apply(0.3), 0.3);
+
+    // CSS ease-in-out is symmetric about the middle
+    let ease_in_out = Easing::Bezier(0.42, 0.0, 0.58, 1.0);
+    assert_near(ease_in_out.apply(0.5), 0.5);
+    assert_near(ease_in_out.apply(0.2) + ease_in_out.apply(0.8), 1.0);
+    assert!(ease_in_out.apply(0.2) < 0.2);
+}
+
+#[test]
+fn bezier_clamps_x_control_points() {
+    // Clamped to (0, 0, 1, 1), whose y equals its x all the way along
+    let clamped = Easing::Bezier(-1.0, 0.0, 2.0, 1.0);
+
+    for &t in &[0.1, 0.3, 0.5, 0.9] {
+        assert_near(clamped.apply(t), t);
+    }
+}
+
+#[test]
+fn slerp_turns_at_constant_speed() {
+    let start = Quat::default();
+    let end = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), FRAC_PI_2);
+
+    for &t in &[0.0, 0.25, 0.5, 1.0] {
+        let angle = FRAC_PI_2 * t;
+        let turned = start.slerp(end, t).rotate(Vec3::new(1.0, 0.0, 0.0));
+        assert!((turned - Vec3::new(angle.cos(), angle.sin(), 0.0)).length() < 1e-9, "t = {} gave {}", t, turned);
+    }
+}
+
+#[test]
+fn slerp_takes_the_shorter_arc() {
+    let start = Quat::default();
+    let end = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), FRAC_PI_2);
+    let negated = Quat::new(-end[0], -end[1], -end[2], -end[3]);
+
+    let halfway = start.slerp(negated, 0.5).rotate(Vec3::new(1.0, 0.0, 0.0));
+    let angle = FRAC_PI_2 / 2.0;
+    assert!((halfway - Vec3::new(angle.cos(), angle.sin(), 0.0)).length() < 1e-9, "{}", halfway);
+}
+
+#[test]
+fn orientation_tracks_slerp_between_keyframes() {
+    let mut track = Track::new(LoopMode::Once);
+    track.insert(0.0, Quat::default(), Easing::Linear);
+    track.insert(10.0, Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), FRAC_PI_2), Easing::Linear);
+
+    let turned = track.sample(5.0).unwrap().rotate(Vec3::new(0.0, 0.0, 1.0));
+    let angle = FRAC_PI_2 / 2.0;
+    assert!((turned - Vec3::new(angle.sin(), 0.0, angle.cos())).length() < 1e-9, "{}", turned);
+}
+
+#[test]
+fn parse_accepts_every_easing() {
+    assert_eq!(Easing::parse("step"), Ok(Easing::Step));
+    assert_eq!(Easing::parse("linear"), Ok(Easing::Linear));
+    assert_eq!(Easing::parse("ease_in"), Ok(Easing::EaseIn));
+    assert_eq!(Easing::parse("ease_out"), Ok(Easing::EaseOut));
+    assert_eq!(Easing::parse(" ease_in_out "), Ok(Easing::EaseInOut));
+    assert_eq!(Easing::parse("bezier(0.1, 0.2,0.3 , 0.4)"), Ok(Easing::Bezier(0.1, 0.2, 0.3, 0.4)));
+}
+
+#[test]
+fn parse_rejects_bad_easings() {
+    for name in &["bounce", "bezier(0.1, 0.2, 0.3)", "bezier(0.1, 0.2, 0.3, 0.4, 0.5)", "bezier(0.1, x, 0.3, 0.4)", "bezier(0.1, 0.2, 0.3, 0.4"] {
+        assert_eq!(Easing::parse(name), Err(Error::UnknownEasing(name.to_string())), "{}", name);
+    }
+}
+
+// Moving an object by keyframe must not read as velocity to a Verlet body
+#[test]
+fn animated_position_keeps_bodies_still() {
+    let world = PhysicsWorld::new(Vec3::default(), 1.0, Integrator::Verlet);
+    let mut objects = vec![make_cube(2.0)];
+    objects[0].body = Some(RigidBody::default());
+
+    let mut animation = ObjectAnimation::new(0);
+    animation.position.insert(0.0, Vec3::new(10.0, 0.0, 0.0), Easing::Linear);
+
+    world.step(&mut objects);
+    animation.apply(&mut objects[0], 0.0);
+    world.step(&mut objects);
+
+    assert!((objects[0].position - Vec3::new(10.0, 0.0, 0.0)).length() < 1e-12, "{}", objects[0].position);
+    assert!(objects[0].velocity.length() < 1e-12, "{}", objects[0].velocity);
+}
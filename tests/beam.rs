@@ -0,0 +1,78 @@
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+
+use vector_demo::eng::beam::{chain_segments, improve_two_opt, measure, Polyline};
+
+fn polyline(points: &[(f64, f64)]) -> Polyline {
+    return Polyline {points: points.to_vec()};
+}
+
+#[test]
+fn chains_segments_sharing_endpoints() {
+    // A square drawn as four segments in mixed directions, plus one lone segment
+    let lines = [
+        0.0, 0.0, 1.0, 0.0,
+        1.0, 1.0, 1.0, 0.0,
+        1.0, 1.0, 0.0, 1.0,
+        0.0, 0.0, 0.0, 1.0,
+        3.0, 3.0, 4.0, 3.0,
+    ];
+
+    let mut polylines = chain_segments(&lines, 1e-6);
+    polylines.sort_by_key(|polyline| polyline.points.len());
+
+    assert_eq!(polylines.len(), 2);
+    assert_eq!(polylines[0].points.len(), 2);
+    assert!(polylines[1].is_closed());
+    assert_eq!(polylines[1].points.len(), 5);
+}
+
+#[test]
+fn joins_endpoints_within_tolerance_across_cell_boundaries() {
+    let tolerance = 1e-3;
+
+    // The shared endpoints are a tenth of the tolerance apart, on either side of a cell edge
+    for boundary in [0.001, 0.0015, 0.002] {
+        let lines = [
+            -1.0, 0.0, boundary - 0.00005, 0.0,
+            boundary + 0.00005, 0.0, 1.0, 0.0,
+        ];
+        assert_eq!(chain_segments(&lines, tolerance).len(), 1, "split at {}", boundary);
+    }
+
+    // Further apart than the tolerance they stay separate
+    let lines = [-1.0, 0.0, 0.0, 0.0, 0.002, 0.0, 1.0, 0.0];
+    assert_eq!(chain_segments(&lines, tolerance).len(), 2);
+}
+
+#[test]
+fn two_opt_untangles_a_crossing_tour() {
+    let start = (0.0, 0.0);
+    let mut polylines = vec![
+        polyline(&[(0.0, 1.0), (0.0, 1.0)]),
+        polyline(&[(1.0, 0.0), (1.0, 0.0)]),
+        polyline(&[(1.0, 1.0), (1.0, 1.0)]),
+    ];
+    assert!((measure(&polylines, start).blank_distance - (2.0 + 2.0 * 2.0_f64.sqrt())).abs() < 1e-12);
+
+    improve_two_opt(&mut polylines, start, 4);
+
+    assert!((measure(&polylines, start).blank_distance - 4.0).abs() < 1e-12);
+}
+
+// Reversing the tail of the tour also changes the move back to the start
+#[test]
+fn two_opt_counts_the_move_back_to_start() {
+    let start = (0.0, 0.0);
+    let mut polylines = vec![
+        polyline(&[(0.0, 0.0), (5.0, 0.0)]),
+        polyline(&[(6.0, 0.0), (7.0, 0.0)]),
+        polyline(&[(7.0, 1.0), (5.0, 0.5)]),
+    ];
+    let before = measure(&polylines, start).blank_distance;
+
+    improve_two_opt(&mut polylines, start, 4);
+
+    assert!(measure(&polylines, start).blank_distance <= before + 1e-12);
+}
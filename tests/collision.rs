@@ -0,0 +1,142 @@
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+
+use vector_demo::eng::bounds::{Aabb, BoundingSphere};
+use vector_demo::eng::collision::*;
+use vector_demo::eng::euler::Vec3;
+use vector_demo::eng::physics::{PhysicsWorld, RigidBody};
+use vector_demo::eng::renderer::{make_cube, GameObject};
+
+fn close(a: Vec3, b: Vec3) -> bool {
+    return (a - b).length() < 1e-9;
+}
+
+fn solid_cube(x: f64) -> GameObject {
+    let mut cube = make_cube(2.0);
+    cube.position = Vec3::new(x, 0.0, 0.0);
+    cube.body = Some(RigidBody::default());
+    return cube;
+}
+
+#[test]
+fn overlapping_spheres_touch_between_their_centres() {
+    let a = BoundingSphere::new(Vec3::default(), 2.0);
+    let b = BoundingSphere::new(Vec3::new(3.0, 0.0, 0.0), 2.0);
+
+    let (point, normal, depth) = sphere_sphere(&a, &b).unwrap();
+    assert!(close(normal, Vec3::new(1.0, 0.0, 0.0)));
+    assert!((depth - 1.0).abs() < 1e-12);
+    assert!(close(point, Vec3::new(1.5, 0.0, 0.0)));
+
+    assert!(sphere_sphere(&a, &BoundingSphere::new(Vec3::new(4.5, 0.0, 0.0), 0.4)).is_none());
+}
+
+#[test]
+fn boxes_separate_along_the_least_overlap() {
+    let a = Aabb::new(Vec3::new(0.0, 0.0, 0.0), Vec3::new(2.0, 2.0, 2.0));
+    let b = Aabb::new(Vec3::new(1.0, 1.75, 0.5), Vec3::new(3.0, 3.75, 2.5));
+
+    let (point, normal, depth) = aabb_aabb(&a, &b).unwrap();
+    assert!(close(normal, Vec3::new(0.0, 1.0, 0.0)));
+    assert!((depth - 0.25).abs() < 1e-12);
+    assert!(close(point, Vec3::new(1.5, 1.875, 1.25)));
+
+    let apart = Aabb::new(Vec3::new(2.5, 0.0, 0.0), Vec3::new(3.0, 1.0, 1.0));
+    assert!(aabb_aabb(&a, &apart).is_none());
+}
+
+#[test]
+fn closest_points_of_crossing_and_parallel_segments() {
+    // Skew segments crossing over each other one unit apart
+    let (on_a, on_b) = closest_points_segments(
+        Vec3::new(-1.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.5, -1.0, 1.0), Vec3::new(0.5, 1.0, 1.0));
+    assert!(close(on_a, Vec3::new(0.5, 0.0, 0.0)) && close(on_b, Vec3::new(0.5, 0.0, 1.0)));
+
+    // Closest where one segment ends
+    let (on_a, on_b) = closest_points_segments(
+        Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(3.0, -1.0, 0.0), Vec3::new(3.0, 1.0, 0.0));
+    assert!(close(on_a, Vec3::new(1.0, 0.0, 0.0)) && close(on_b, Vec3::new(3.0, 0.0, 0.0)));
+
+    // Parallel segments still give points one unit apart
+    let (on_a, on_b) = closest_points_segments(
+        Vec3::new(0.0, 0.0, 0.0), Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(1.0, 1.0, 0.0), Vec3::new(3.0, 1.0, 0.0));
+    assert!(((on_b - on_a).length() - 1.0).abs() < 1e-12);
+
+    // A degenerate segment acts as a point
+    let (on_a, on_b) = closest_points_segments(
+        Vec3::new(0.0, 2.0, 0.0), Vec3::new(0.0, 2.0, 0.0),
+        Vec3::new(-1.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+    assert!(close(on_a, Vec3::new(0.0, 2.0, 0.0)) && close(on_b, Vec3::default()));
+}
+
+#[test]
+fn sweep_and_prune_matches_brute_force() {
+    let boxes: Vec::<Aabb> = (0..40).map(|idx| {
+        let x = ((idx * 37) % 23) as f64 * 0.7;
+        let y = ((idx * 11) % 7) as f64 * 0.9;
+        let size = 0.5 + (idx % 3) as f64;
+        return Aabb::new(Vec3::new(x, y, 0.0), Vec3::new(x + size, y + size, 1.0));
+    }).collect();
+
+    let mut expected: Vec::<(usize, usize)> = Vec::new();
+    for a in 0..boxes.len() {
+        for b in a + 1..boxes.len() {
+            if boxes[a].intersects(&boxes[b]) {
+                expected.push((a, b));
+            }
+        }
+    }
+
+    assert!(!expected.is_empty());
+    assert_eq!(sweep_and_prune(&boxes), expected);
+}
+
+#[test]
+fn detection_is_off_by_default() {
+    let objects = vec![solid_cube(0.0), solid_cube(1.0)];
+    assert!(CollisionDetector::default().detect(&objects).is_empty());
+}
+
+#[test]
+fn objects_without_bodies_collide_too() {
+    let mut bullet = make_cube(2.0);
+    bullet.position = Vec3::new(0.5, 0.0, 0.0);
+    let empty = GameObject::default();
+    let objects = vec![solid_cube(0.0), bullet, empty, solid_cube(10.0)];
+
+    let contacts = CollisionDetector::new(NarrowPhase::Sphere, 0.0).detect(&objects);
+    assert_eq!(contacts.len(), 1);
+    assert_eq!((contacts[0].a, contacts[0].b), (0, 1));
+}
+
+// A bodiless bullet pushes a body away but keeps going; two bodiless objects pass through
+#[test]
+fn resolve_moves_only_bodies() {
+    let world = PhysicsWorld::default();
+    let detector = CollisionDetector::new(NarrowPhase::Sphere, 0.0);
+
+    let mut bullet = make_cube(2.0);
+    bullet.position = Vec3::new(-1.0, 0.0, 0.0);
+    bullet.velocity = Vec3::new(2.0, 0.0, 0.0);
+    let mut objects = vec![bullet, solid_cube(0.0)];
+
+    let contacts = detector.detect(&objects);
+    world.resolve(&mut objects, &contacts);
+    assert!(objects[1].velocity[0] > 0.0, "{}", objects[1].velocity);
+    assert!(close(objects[0].velocity, Vec3::new(2.0, 0.0, 0.0)));
+    assert!(close(objects[0].position, Vec3::new(-1.0, 0.0, 0.0)));
+
+    let mut scenery = make_cube(2.0);
+    scenery.position = Vec3::new(0.5, 0.0, 0.0);
+    let mut objects = vec![objects[0].clone(), scenery];
+    let contacts = detector.detect(&objects);
+    assert_eq!(contacts.len(), 1);
+
+    world.resolve(&mut objects, &contacts);
+    assert!(close(objects[0].position, Vec3::new(-1.0, 0.0, 0.0)) && close(objects[0].velocity, Vec3::new(2.0, 0.0, 0.0)));
+    assert!(close(objects[1].position, Vec3::new(0.5, 0.0, 0.0)) && close(objects[1].velocity, Vec3::default()));
+}
@@ -0,0 +1,57 @@
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+
+use vector_demo::eng::bounds::BoundingSphere;
+use vector_demo::eng::euler::*;
+use vector_demo::eng::frustum::Frustum;
+use vector_demo::eng::renderer::{Camera, GameObject, Renderer};
+
+// The default renderer has a 90 degree field of view, a square aspect ratio and planes at 1 and
+// 1000, so in camera space the side planes are x = +-z and y = +-z
+fn frustum() -> Frustum {
+    return Renderer::default().frustum();
+}
+
+#[test]
+fn sphere_inside_is_visible() {
+    assert!(frustum().intersects_sphere(&BoundingSphere::new(Vec3::new(0.0, 0.0, 50.0), 1.0)));
+}
+
+#[test]
+fn sphere_outside_each_plane_is_culled() {
+    let outside = [
+        Vec3::new(-80.0, 0.0, 50.0),     // Left
+        Vec3::new(80.0, 0.0, 50.0),      // Right
+        Vec3::new(0.0, -80.0, 50.0),     // Bottom
+        Vec3::new(0.0, 80.0, 50.0),      // Top
+        Vec3::new(0.0, 0.0, -5.0),       // Near
+        Vec3::new(0.0, 0.0, 1100.0),     // Far
+    ];
+
+    for (plane, center) in frustum().planes.iter().zip(outside.iter()) {
+        let sphere = BoundingSphere::new(*center, 2.0);
+        assert!(Frustum::signed_distance(plane, *center) < -2.0, "{:?} is not outside {:?}", center, plane);
+        assert!(!frustum().intersects_sphere(&sphere), "{:?}", center);
+    }
+}
+
+#[test]
+fn sphere_straddling_the_near_plane_is_visible() {
+    let sphere = BoundingSphere::new(Vec3::new(0.0, 0.0, 0.5), 2.0);
+    assert!(frustum().intersects_sphere(&sphere));
+}
+
+#[test]
+fn long_object_past_the_corner_is_culled_by_its_box() {
+    // A rod along x whose bounding sphere reaches into the view, while the rod itself stays
+    // beyond the right plane
+    let renderer = Renderer::default();
+    let camera = Camera::new(Vec3::default(), IDENTITY4X4);
+    let rod = GameObject::new(Vec3::new(75.0, 0.0, 50.0), Vec3::default(), IDENTITY4X4, IDENTITY4X4,
+        vec![Vec3::new(-20.0, 0.0, 0.0), Vec3::new(20.0, 0.0, 0.0)], vec![0, 1]);
+
+    let sphere = rod.bounding_sphere().transform(&(camera.view_matrix() * rod.model_matrix()));
+    assert!(renderer.frustum().intersects_sphere(&sphere));
+    assert!(!renderer.is_visible(&renderer.frustum(), &rod, &camera.view_matrix()));
+}
@@ -0,0 +1,220 @@
+#![allow(clippy::needless_return)]
+#![cfg(not(target_arch = "wasm32"))]
+
+extern crate proptest;
+extern crate vector_demo;
+
+use std::f64::consts::PI;
+
+use proptest::prelude::*;
+
+use vector_demo::eng::euler::*;
+
+const TOLERANCE: f64 = 1e-9;
+
+fn assert_close(actual: &[f64], expected: &[f64], tolerance: f64) {
+    assert_eq!(actual.len(), expected.len());
+    for (idx, (a, e)) in actual.iter().zip(expected.iter()).enumerate() {
+        assert!((a - e).abs() <= tolerance, "element {}: {} != {} in {:?} vs {:?}", idx, a, e, actual, expected);
+    }
+}
+
+#[test]
+fn vector_operators() {
+    let a = Vec3::new(1.0, 2.0, 3.0);
+    let b = Vec3::new(-4.0, 0.5, 2.0);
+
+    assert_eq!((a + b).elems, [-3.0, 2.5, 5.0]);
+    assert_eq!((a - b).elems, [5.0, 1.5, 1.0]);
+    assert_eq!((a * 2.0).elems, [2.0, 4.0, 6.0]);
+    assert_eq!((a / 2.0).elems, [0.5, 1.0, 1.5]);
+    assert_eq!(a.dot(b), 3.0);
+    assert_eq!(a.cross(b).elems, [2.5, -14.0, 8.5]);
+    assert_eq!(Vec3::new(3.0, 4.0, 0.0).length(), 5.0);
+    assert_eq!(Vec3::new(0.0, 0.0, -2.0).normalize().elems, [0.0, 0.0, -1.0]);
+    assert_eq!(Vec3::<f64>::default().normalize().elems, [0.0, 0.0, 0.0]);
+
+    let c = Vec4::new(1.0, 2.0, 3.0, 4.0);
+    assert_eq!((c + c).elems, [2.0, 4.0, 6.0, 8.0]);
+    assert_eq!((c - c).elems, [0.0; 4]);
+    assert_eq!(c.dot(c), 30.0);
+}
+
+#[test]
+fn matrix_products() {
+    let a = Mat4::new([
+        1.0, 2.0, 3.0, 4.0,
+        5.0, 6.0, 7.0, 8.0,
+        9.0, 10.0, 11.0, 12.0,
+        13.0, 14.0, 15.0, 16.0,
+    ]);
+
+    assert_eq!((a * IDENTITY4X4).elems, a.elems);
+    assert_eq!((IDENTITY4X4 * a).elems, a.elems);
+    assert_eq!((a * Vec4::new(1.0, 0.0, -1.0, 2.0)).elems, [6.0, 14.0, 22.0, 30.0]);
+    assert_eq!((a * a)[0], [90.0, 100.0, 110.0, 120.0]);
+    assert_eq!((a * 2.0)[3], [26.0, 28.0, 30.0, 32.0]);
+
+    let b = Mat3::new([1.0, 2.0, 3.0, 0.0, 1.0, 4.0, 5.0, 6.0, 0.0]);
+    assert_eq!((b * Vec3::new(1.0, 1.0, 1.0)).elems, [6.0, 5.0, 11.0]);
+    assert_eq!(b.determinant(), 1.0);
+    assert_eq!(b.transpose().transpose().elems, b.elems);
+    assert_close(&(b * b.inverse()).elems, &IDENTITY3X3.elems, TOLERANCE);
+}
+
+#[test]
+fn rotations_turn_the_expected_way() {
+    let quarter = PI / 2.0;
+
+    assert_close(&z_rotation_matrix(quarter).transform_point(Vec3::new(1.0, 0.0, 0.0)).elems, &[0.0, 1.0, 0.0], TOLERANCE);
+    assert_close(&x_rotation_matrix(quarter).transform_point(Vec3::new(0.0, 1.0, 0.0)).elems, &[0.0, 0.0, 1.0], TOLERANCE);
+    assert_close(&y_rotation_matrix(quarter).transform_point(Vec3::new(0.0, 0.0, 1.0)).elems, &[1.0, 0.0, 0.0], TOLERANCE);
+    assert_close(
+        &axis_angle_rotation_matrix(Vec3::new(0.0, 0.0, 5.0), quarter).elems,
+        &z_rotation_matrix(quarter).elems,
+        TOLERANCE);
+}
+
+#[test]
+fn translation_moves_points_but_not_directions() {
+    let translation = translation_matrix(1.0, -2.0, 3.0);
+
+    assert_eq!(translation.transform_point(Vec3::new(1.0, 1.0, 1.0)).elems, [2.0, -1.0, 4.0]);
+    assert_eq!(translation.transform_direction(Vec3::new(1.0, 1.0, 1.0)).elems, [1.0, 1.0, 1.0]);
+    assert_eq!(translation.translation().elems, [1.0, -2.0, 3.0]);
+}
+
+#[test]
+fn quaternions_match_matrices() {
+    let axis = Vec3::new(1.0, -2.0, 0.5);
+    let quat = Quat::from_axis_angle(axis, 0.9);
+    let point = Vec3::new(3.0, 1.0, -2.0);
+
+    assert_close(&quat.rotate(point).elems, &axis_angle_rotation_matrix(axis, 0.9).transform_point(point).elems, TOLERANCE);
+    assert_close(&Quat::from_rotation_matrix(quat.to_matrix()).to_matrix().elems, &quat.to_matrix().elems, TOLERANCE);
+    assert_close(&quat.slerp(quat, 0.3).elems, &quat.elems, TOLERANCE);
+}
+
+#[test]
+fn casting_to_f32_keeps_values() {
+    let matrix = z_rotation_matrix(0.3) * scale_matrix(2.0, 2.0, 2.0);
+    let narrowed: Mat4<f32> = matrix.cast();
+    let widened: Mat4<f64> = narrowed.cast();
+
+    assert_close(&widened.elems, &matrix.elems, 1e-6);
+    assert_close(
+        &z_rotation_matrix(0.3f32).cast::<f64>().elems,
+        &z_rotation_matrix(0.3).elems,
+        1e-6);
+}
+
+fn component() -> impl Strategy<Value = f64> {
+    return -100.0..100.0f64;
+}
+
+fn vec3() -> impl Strategy<Value = Vec3> {
+    return (component(), component(), component()).prop_map(|(x, y, z)| Vec3::new(x, y, z));
+}
+
+fn unit_axis() -> impl Strategy<Value = Vec3> {
+    return vec3().prop_filter("axis must not be zero", |axis| axis.length() > 1e-3);
+}
+
+fn rotation() -> impl Strategy<Value = Mat4> {
+    return (unit_axis(), -PI..PI).prop_map(|(axis, theta)| axis_angle_rotation_matrix(axis, theta));
+}
+
+// Rotation, non-zero scale and translation
+fn affine() -> impl Strategy<Value = Mat4> {
+    let scale = || prop_oneof![-10.0..-0.1f64, 0.1..10.0f64];
+
+    return (rotation(), scale(), scale(), scale(), vec3()).prop_map(|(rotation, x, y, z, offset)| {
+        translation_matrix(offset[0], offset[1], offset[2]) * rotation * scale_matrix(x, y, z)
+    });
+}
+
+proptest! {
+    #[test]
+    fn addition_commutes(a in vec3(), b in vec3()) {
+        prop_assert_eq!((a + b).elems, (b + a).elems);
+    }
+
+    #[test]
+    fn cross_product_is_perpendicular(a in vec3(), b in vec3()) {
+        let cross = a.cross(b);
+        let scale = a.length() * b.length() * (a.length() + b.length()) + 1.0;
+
+        prop_assert!(cross.dot(a).abs() <= 1e-12 * scale);
+        prop_assert!(cross.dot(b).abs() <= 1e-12 * scale);
+    }
+
+    #[test]
+    fn matrix_product_is_associative(a in affine(), b in affine(), c in affine()) {
+        let left = (a * b) * c;
+        let right = a * (b * c);
+        let scale = left.elems.iter().fold(1.0f64, |max, elem| max.max(elem.abs()));
+
+        for idx in 0..16 {
+            prop_assert!((left.elems[idx] - right.elems[idx]).abs() <= 1e-10 * scale);
+        }
+    }
+
+    #[test]
+    fn inverse_round_trips(matrix in affine(), point in vec3()) {
+        let inverse = matrix.try_inverse(INVERSE_EPSILON).unwrap();
+        let back = inverse.transform_point(matrix.transform_point(point));
+
+        for axis in 0..3 {
+            prop_assert!((back[axis] - point[axis]).abs() <= 1e-6 * (1.0 + point.length()));
+        }
+    }
+
+    #[test]
+    fn affine_inverse_matches_general_inverse(matrix in affine()) {
+        let general = matrix.inverse();
+        let affine = matrix.try_affine_inverse(INVERSE_EPSILON).unwrap();
+
+        for idx in 0..16 {
+            prop_assert!((general.elems[idx] - affine.elems[idx]).abs() <= 1e-8 * (1.0 + general.elems[idx].abs()));
+        }
+    }
+
+    #[test]
+    fn rotations_are_orthonormal(rotation in rotation()) {
+        let linear = rotation.upper_left();
+        let product = linear * linear.transpose();
+
+        for idx in 0..9 {
+            prop_assert!((product.elems[idx] - IDENTITY3X3.elems[idx]).abs() <= TOLERANCE);
+        }
+        prop_assert!((linear.determinant() - 1.0).abs() <= TOLERANCE);
+    }
+
+    #[test]
+    fn rotations_preserve_length(rotation in rotation(), point in vec3()) {
+        let rotated = rotation.transform_point(point);
+        prop_assert!((rotated.length() - point.length()).abs() <= TOLERANCE * (1.0 + point.length()));
+    }
+
+    #[test]
+    fn rigid_inverse_matches_general_inverse(rotation in rotation(), offset in vec3()) {
+        let rigid = translation_matrix(offset[0], offset[1], offset[2]) * rotation;
+        let general = rigid.inverse();
+        let fast = rigid.rigid_inverse();
+
+        for idx in 0..16 {
+            prop_assert!((general.elems[idx] - fast.elems[idx]).abs() <= 1e-9);
+        }
+    }
+
+    #[test]
+    fn rotation_vector_round_trips(axis in unit_axis(), theta in 0.01..3.1f64) {
+        let matrix = axis_angle_rotation_matrix(axis, theta);
+        let vector = rotation_vector(matrix);
+        let rebuilt = axis_angle_rotation_matrix(vector, vector.length());
+
+        for idx in 0..16 {
+            prop_assert!((rebuilt.elems[idx] - matrix.elems[idx]).abs() <= 1e-6);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+
+use vector_demo::eng::font::{glyph, layout, make_text, measure, HorizontalAlign, TextStyle, VerticalAlign, GLYPH_HEIGHT};
+
+// min_x, max_x, min_y, max_y over every polyline
+fn bounds(polylines: &[Vec<(f64, f64)>]) -> (f64, f64, f64, f64) {
+    let points = polylines.iter().flatten();
+    return (
+        points.clone().map(|point| point.0).fold(f64::MAX, f64::min),
+        points.clone().map(|point| point.0).fold(f64::MIN, f64::max),
+        points.clone().map(|point| point.1).fold(f64::MAX, f64::min),
+        points.map(|point| point.1).fold(f64::MIN, f64::max),
+    );
+}
+
+// One font unit per unit of output, so layouts can be compared with the glyph grid
+fn unit_style(horizontal_align: HorizontalAlign, vertical_align: VerticalAlign) -> TextStyle {
+    return TextStyle::new(GLYPH_HEIGHT, horizontal_align, vertical_align);
+}
+
+#[test]
+fn lowercase_letters_share_the_capitals() {
+    assert_eq!(glyph('a'), glyph('A'));
+    assert_eq!(glyph('z'), glyph('Z'));
+    assert_ne!(glyph('A'), glyph('B'));
+}
+
+#[test]
+fn unknown_characters_draw_a_question_mark() {
+    assert_eq!(glyph('€'), glyph('?'));
+    assert_eq!(glyph('\t'), glyph('?'));
+    assert!(glyph(' ').is_empty());
+}
+
+#[test]
+fn glyphs_stay_on_the_grid() {
+    for character in "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789.!?-+=*:/'\"()<>_".chars() {
+        for stroke in glyph(character).iter() {
+            assert!(stroke.len() >= 2, "{:?} has a stroke of {} points", character, stroke.len());
+            for &(x, y) in stroke.iter() {
+                assert!((0..=4).contains(&x) && (-1..=6).contains(&y), "{:?} reaches ({}, {})", character, x, y);
+            }
+        }
+    }
+}
+
+#[test]
+fn measure_counts_advances_and_lines() {
+    assert_eq!(measure(""), (0.0, 6.0));
+    assert_eq!(measure("A"), (4.0, 6.0));
+    assert_eq!(measure("AB"), (10.0, 6.0));
+    assert_eq!(measure("AB\nC"), (10.0, 15.0));
+    assert_eq!(measure("A\nLONG"), (22.0, 15.0));
+    assert_eq!(measure("A\n"), (4.0, 15.0));
+}
+
+#[test]
+fn left_aligned_text_starts_at_the_anchor() {
+    let polylines = layout("AB", &unit_style(HorizontalAlign::Left, VerticalAlign::Bottom));
+    assert_eq!(bounds(&polylines), (0.0, 10.0, 0.0, 6.0));
+
+    // The first glyph is laid out exactly as drawn on its grid
+    let first = &polylines[0];
+    let expected: Vec<(f64, f64)> = glyph('A')[0].iter().map(|&(x, y)| (x as f64, y as f64)).collect();
+    assert_eq!(first, &expected);
+}
+
+#[test]
+fn center_and_right_alignment_shift_each_line() {
+    let center = layout("AB", &unit_style(HorizontalAlign::Center, VerticalAlign::Bottom));
+    assert_eq!(bounds(&center), (-5.0, 5.0, 0.0, 6.0));
+
+    let right = layout("AB", &unit_style(HorizontalAlign::Right, VerticalAlign::Bottom));
+    assert_eq!(bounds(&right), (-10.0, 0.0, 0.0, 6.0));
+}
+
+#[test]
+fn vertical_alignment_moves_the_block() {
+    let top = layout("H", &unit_style(HorizontalAlign::Left, VerticalAlign::Top));
+    assert_eq!(bounds(&top), (0.0, 4.0, -6.0, 0.0));
+
+    let middle = layout("H", &unit_style(HorizontalAlign::Left, VerticalAlign::Middle));
+    assert_eq!(bounds(&middle), (0.0, 4.0, -3.0, 3.0));
+}
+
+#[test]
+fn newlines_start_a_lower_line_aligned_on_its_own() {
+    let polylines = layout("I\nIII", &unit_style(HorizontalAlign::Center, VerticalAlign::Bottom));
+
+    // 'I' has three strokes; the first line's come first
+    assert_eq!(polylines.len(), 12);
+    assert_eq!(bounds(&polylines[..3]), (-2.0, 2.0, 9.0, 15.0));
+    assert_eq!(bounds(&polylines[3..]), (-8.0, 8.0, 0.0, 6.0));
+}
+
+#[test]
+fn unknown_characters_lay_out_as_question_marks() {
+    let style = TextStyle::default();
+    assert_eq!(layout("a€", &style), layout("A?", &style));
+}
+
+#[test]
+fn size_scales_the_layout() {
+    let full = layout("AB", &unit_style(HorizontalAlign::Left, VerticalAlign::Bottom));
+    let half = layout("AB", &TextStyle::new(GLYPH_HEIGHT / 2.0, HorizontalAlign::Left, VerticalAlign::Bottom));
+
+    for (full_line, half_line) in full.iter().zip(half.iter()) {
+        for (&(x, y), &(half_x, half_y)) in full_line.iter().zip(half_line.iter()) {
+            assert_eq!((x / 2.0, y / 2.0), (half_x, half_y));
+        }
+    }
+}
+
+#[test]
+fn make_text_chains_each_polyline() {
+    let style = unit_style(HorizontalAlign::Left, VerticalAlign::Bottom);
+    let text = make_text("HI", &style);
+    let polylines = layout("HI", &style);
+
+    let point_count: usize = polylines.iter().map(|polyline| polyline.len()).sum();
+    let segment_count: usize = polylines.iter().map(|polyline| polyline.len() - 1).sum();
+    assert_eq!(text.points.len(), point_count);
+    assert_eq!(text.connections.len(), 2 * segment_count);
+    assert!(text.validate().is_ok());
+
+    // Flat in the xy-plane, and no segment bridges two strokes
+    assert!(text.points.iter().all(|point| point[2] == 0.0));
+    for pair in text.connections.chunks(2) {
+        assert_eq!(pair[1], pair[0] + 1);
+    }
+    for (point, &(x, y)) in text.points.iter().zip(polylines.iter().flatten()) {
+        assert_eq!((point[0], point[1]), (x, y));
+    }
+}
+
+#[test]
+fn make_text_of_blank_text_is_empty() {
+    let text = make_text("  ", &TextStyle::default());
+    assert!(text.points.is_empty());
+    assert!(text.connections.is_empty());
+}
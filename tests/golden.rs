@@ -0,0 +1,114 @@
+#![allow(clippy::needless_return)]
+#![cfg(not(target_arch = "wasm32"))]
+
+// Renders fixed scenes and compares the line buffer with the files in tests/golden. After an
+// intended change to the output, regenerate them with
+//
+//     UPDATE_GOLDEN=1 cargo test --test golden
+//
+// and review the diff of tests/golden before committing.
+
+extern crate vector_demo;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use vector_demo::eng::euler::*;
+use vector_demo::eng::renderer::{make_cube, make_skeleton1, make_tesseract, Camera, GameObject, Renderer};
+
+// Allowed difference per coordinate, relative to the coordinate's size for values above one
+const TOLERANCE: f64 = 1e-9;
+
+fn golden_path(name: &str) -> PathBuf {
+    return PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden").join(format!("{}.txt", name));
+}
+
+// One segment per line as "tail_x tail_y head_x head_y"
+fn format_lines(lines: &[f64]) -> String {
+    let mut text = String::new();
+
+    for line in lines.chunks_exact(4) {
+        text.push_str(&format!("{:?} {:?} {:?} {:?}\n", line[0], line[1], line[2], line[3]));
+    }
+    return text;
+}
+
+fn parse_lines(text: &str) -> Vec<f64> {
+    return text.split_whitespace().map(|value| value.parse::<f64>().expect("golden files hold numbers only")).collect();
+}
+
+fn check_golden(name: &str, lines: &[f64]) {
+    let path = golden_path(name);
+
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, format_lines(lines)).expect("could not write golden file");
+        return;
+    }
+
+    let text = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("missing {}; run with UPDATE_GOLDEN=1 to create it", path.display()));
+    let expected = parse_lines(&text);
+
+    assert_eq!(lines.len(), expected.len(), "{}: expected {} segments, rendered {}", name, expected.len() / 4, lines.len() / 4);
+
+    for (idx, (actual, expected)) in lines.iter().zip(expected.iter()).enumerate() {
+        let allowed = TOLERANCE * expected.abs().max(1.0);
+        assert!((actual - expected).abs() <= allowed,
+            "{}: segment {} coordinate {} is {} but the golden frame has {}", name, idx / 4, idx % 4, actual, expected);
+    }
+}
+
+fn render(objects: &[GameObject], camera: &Camera) -> Vec<f64> {
+    let mut renderer = Renderer::default();
+    return renderer.render_frame(objects, camera);
+}
+
+fn rotated(mut object: GameObject, x_r: f64, y_r: f64, z_r: f64) -> GameObject {
+    object.orientation = z_rotation_matrix(z_r) * y_rotation_matrix(y_r) * x_rotation_matrix(x_r);
+    return object;
+}
+
+#[test]
+fn cube_front() {
+    check_golden("cube_front", &render(&[make_cube(100.0)], &Camera::default()));
+}
+
+#[test]
+fn cube_rotated() {
+    let cube = rotated(make_cube(100.0), 0.4, 0.7, 0.1);
+    check_golden("cube_rotated", &render(&[cube], &Camera::default()));
+}
+
+#[test]
+fn cube_scaled_offset() {
+    let mut cube = rotated(make_cube(60.0), -0.3, 1.2, 0.0);
+    cube.scale = Vec3::new(1.5, 0.5, 1.0);
+    cube.position = Vec3::new(40.0, -25.0, 30.0);
+    check_golden("cube_scaled_offset", &render(&[cube], &Camera::default()));
+}
+
+#[test]
+fn tesseract_rotated() {
+    let tesseract = rotated(make_tesseract(120.0), 0.9, -0.5, 0.3);
+    check_golden("tesseract_rotated", &render(&[tesseract], &Camera::default()));
+}
+
+#[test]
+fn skeleton_side_camera() {
+    let skeleton = rotated(make_skeleton1(30.0), 0.2, 0.0, -0.4);
+    let camera = Camera::new(Vec3::new(150.0, 20.0, -120.0), y_rotation_matrix(-0.8));
+    check_golden("skeleton_side_camera", &render(&[skeleton], &camera));
+}
+
+#[test]
+fn mixed_scene_tilted_camera() {
+    let mut cube = rotated(make_cube(50.0), 0.1, 0.2, 0.3);
+    cube.position = Vec3::new(-80.0, 0.0, 50.0);
+    let mut tesseract = rotated(make_tesseract(60.0), 0.5, 0.5, 0.0);
+    tesseract.position = Vec3::new(80.0, 20.0, 100.0);
+    let skeleton = rotated(make_skeleton1(20.0), 0.0, 1.0, 0.0);
+
+    let camera = Camera::new(Vec3::new(0.0, 60.0, -250.0), x_rotation_matrix(-0.2) * y_rotation_matrix(0.1));
+    check_golden("mixed_scene_tilted_camera", &render(&[cube, tesseract, skeleton], &camera));
+}
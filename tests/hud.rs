@@ -0,0 +1,24 @@
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+
+use vector_demo::eng::font::{OverlayText, TextStyle};
+use vector_demo::eng::hud::{Hud, HudElement};
+
+fn width(lines: &[f64]) -> f64 {
+    let xs = lines.iter().step_by(2);
+    return xs.clone().cloned().fold(f64::MIN, f64::max) - xs.cloned().fold(f64::MAX, f64::min);
+}
+
+// Text and lines in one layer must agree on how x is scaled
+#[test]
+fn text_follows_the_layers_aspect_correction() {
+    let text = HudElement::Text(OverlayText::new("HUD", 0.0, 0.0, TextStyle::default()));
+
+    let mut hud = Hud::default();
+    hud.add(text);
+    assert_eq!(width(&hud.lines(0.5)), width(&hud.lines(1.0)));
+
+    hud.aspect_correction = true;
+    assert!((width(&hud.lines(0.5)) - 0.5 * width(&hud.lines(1.0))).abs() < 1e-12);
+}
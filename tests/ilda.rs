@@ -0,0 +1,43 @@
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+
+use vector_demo::eng::ilda::{frame_points, IldaRecording, IldaSettings, MAX_RECORDING_FRAMES};
+
+// A stroke from the centre towards a point projected from near the camera plane
+#[test]
+fn strokes_leaving_the_square_are_clipped_before_subdividing() {
+    let settings = IldaSettings::default();
+    let path = [0.0, 0.0, 0.0, 1e12, 0.0, 1.0, f64::INFINITY, 5.0, 1.0];
+
+    let points = frame_points(&path, &settings);
+
+    // The blank move, one unit of lit points up to the edge, then blanked points outside
+    let lit = points.iter().filter(|point| !point.blanked).count();
+    assert_eq!(lit, 1 + settings.points_per_unit as usize);
+    assert!(points.len() < 100, "{} points", points.len());
+    assert!(points.iter().filter(|point| !point.blanked).all(|point| point.x >= 0 && point.y == 0));
+}
+
+#[test]
+fn strokes_inside_the_square_are_drawn_whole() {
+    let settings = IldaSettings::default();
+    let path = [-0.5, 0.0, 0.0, 0.5, 0.0, 1.0];
+
+    let points = frame_points(&path, &settings);
+
+    assert_eq!(points.len(), settings.blank_points + 1 + settings.points_per_unit as usize);
+    assert_eq!(points[points.len() - 1].x, (0.5 * i16::MAX as f64).round() as i16);
+}
+
+#[test]
+fn recording_stops_at_the_frame_limit() {
+    let mut recording = IldaRecording::new(IldaSettings::default());
+
+    for _ in 0..MAX_RECORDING_FRAMES + 10 {
+        recording.add_frame(&[]);
+    }
+
+    assert!(!recording.active);
+    assert_eq!(recording.frames.len(), MAX_RECORDING_FRAMES);
+}
@@ -0,0 +1,238 @@
+#![allow(clippy::needless_return, clippy::redundant_field_names)]
+
+extern crate vector_demo;
+
+use vector_demo::eng::euler::*;
+use vector_demo::eng::input::{Drive, DriveTarget, InputMap, Motion, Source};
+use vector_demo::eng::physics::{Integrator, PhysicsWorld, RigidBody};
+use vector_demo::eng::renderer::{make_cube, Camera, GameObject};
+
+fn key(code: &str) -> Source {
+    return Source::Key(String::from(code));
+}
+
+fn close(a: Vec3, b: Vec3) -> bool {
+    return (a - b).length() < 1e-9;
+}
+
+fn rotated_camera() -> Camera {
+    return Camera::new(Vec3::new(3.0, -2.0, -10.0), y_rotation_matrix(0.6) * x_rotation_matrix(-0.25));
+}
+
+// Where `Camera::view_matrix` puts the eye in the world
+fn eye(camera: &Camera) -> Vec3 {
+    return camera.view_matrix().inverse().transform_point(Vec3::default());
+}
+
+// An input map with "go" held down and driving `motion` of `target` at `rate`
+fn holding(target: DriveTarget, motion: Motion, rate: f64) -> InputMap {
+    let mut input = InputMap::default();
+    input.bind(key("KeyW"), "go", 1.0);
+    input.drives.push(Drive {target: target, motion: motion, action: String::from("go"), rate: rate});
+    input.set_button(key("KeyW"), true);
+    input.update();
+    return input;
+}
+
+#[test]
+fn camera_moves_along_its_own_axes() {
+    let mut camera = rotated_camera();
+    let before = camera;
+
+    holding(DriveTarget::Camera, Motion::MoveX, 2.0).apply(&mut [], &mut camera);
+
+    // The eye moves along the camera's right vector, so the view only slides sideways
+    let right = before.orientation.transform_direction(Vec3::new(1.0, 0.0, 0.0));
+    assert!(close(eye(&camera), eye(&before) + right * 2.0), "{} from {}", eye(&camera), eye(&before));
+
+    let point = Vec3::new(5.0, 1.0, 40.0);
+    let shifted = before.view_matrix().transform_point(point) - camera.view_matrix().transform_point(point);
+    assert!(close(shifted, Vec3::new(2.0, 0.0, 0.0)), "{}", shifted);
+}
+
+#[test]
+fn camera_turns_in_place() {
+    for motion in [Motion::Yaw, Motion::Pitch, Motion::Roll] {
+        let mut camera = rotated_camera();
+        let before = camera;
+
+        holding(DriveTarget::Camera, motion, 0.3).apply(&mut [], &mut camera);
+
+        assert!(close(eye(&camera), eye(&before)), "{:?} moved the eye from {} to {}", motion, eye(&before), eye(&camera));
+        assert!((0..16).any(|idx| (camera.orientation.elems[idx] - before.orientation.elems[idx]).abs() > 1e-6), "{:?} did not turn", motion);
+    }
+
+    // Yawing turns the view about the camera's own up axis: a point straight ahead ends up to the side
+    let mut camera = rotated_camera();
+    let ahead = camera.view_matrix().inverse().transform_point(Vec3::new(0.0, 0.0, 10.0));
+    holding(DriveTarget::Camera, Motion::Yaw, std::f64::consts::FRAC_PI_2).apply(&mut [], &mut camera);
+
+    let seen = camera.view_matrix().transform_point(ahead);
+    assert!(close(seen, Vec3::new(-10.0, 0.0, 0.0)) || close(seen, Vec3::new(10.0, 0.0, 0.0)), "{}", seen);
+}
+
+#[test]
+fn objects_move_along_and_turn_about_their_own_axes() {
+    let mut cube = make_cube(1.0);
+    cube.position = Vec3::new(1.0, 2.0, 3.0);
+    cube.orientation = y_rotation_matrix(std::f64::consts::FRAC_PI_2);
+    let mut objects = vec![cube];
+
+    holding(DriveTarget::Object(0), Motion::MoveZ, 2.0).apply(&mut objects, &mut Camera::default());
+    let forward = objects[0].orientation.transform_direction(Vec3::new(0.0, 0.0, 2.0));
+    assert!(close(objects[0].position, Vec3::new(1.0, 2.0, 3.0) + forward), "{}", objects[0].position);
+
+    let position = objects[0].position;
+    holding(DriveTarget::Object(0), Motion::Yaw, 0.5).apply(&mut objects, &mut Camera::default());
+    assert!(close(objects[0].position, position));
+    assert!((objects[0].orientation.elems[0] - (std::f64::consts::FRAC_PI_2 + 0.5).cos()).abs() < 1e-9);
+
+    // Drives on objects that don't exist are ignored
+    holding(DriveTarget::Object(5), Motion::MoveX, 1.0).apply(&mut objects, &mut Camera::default());
+}
+
+// Driving a body must move it at the drive's rate without building up velocity
+fn check_driven_body(integrator: Integrator) {
+    let world = PhysicsWorld::new(Vec3::default(), 0.5, integrator);
+    let mut cube: GameObject = make_cube(1.0);
+    cube.body = Some(RigidBody::default());
+    let mut objects = vec![cube];
+    let mut camera = Camera::default();
+
+    let mut input = holding(DriveTarget::Object(0), Motion::MoveX, 1.0);
+    world.step(&mut objects);
+
+    for _ in 0..5 {
+        let before = objects[0].position;
+        input.apply(&mut objects, &mut camera);
+        world.step(&mut objects);
+        assert!(close(objects[0].position - before, Vec3::new(1.0, 0.0, 0.0)), "{:?} moved {}", integrator, objects[0].position - before);
+    }
+
+    input.set_button(key("KeyW"), false);
+    input.update();
+    let released = objects[0].position;
+    for _ in 0..3 {
+        input.apply(&mut objects, &mut camera);
+        world.step(&mut objects);
+    }
+    assert!(close(objects[0].position, released), "{:?} kept moving to {}", integrator, objects[0].position);
+}
+
+#[test]
+fn driven_bodies_do_not_gain_velocity() {
+    check_driven_body(Integrator::SemiImplicitEuler);
+    check_driven_body(Integrator::Verlet);
+}
+
+#[test]
+fn sources_parse_from_their_names() {
+    assert_eq!(Source::parse("key:ArrowLeft"), Some(key("ArrowLeft")));
+    assert_eq!(Source::parse(" button:1:7 "), Some(Source::GamepadButton {pad: 1, button: 7}));
+    assert_eq!(Source::parse("axis:0:2"), Some(Source::GamepadAxis {pad: 0, axis: 2}));
+    assert_eq!(Source::parse("spinner:3"), Some(Source::Spinner(3)));
+    assert_eq!(Source::parse("coin:1"), Some(Source::Coin(1)));
+
+    for name in ["key:", "button:1", "axis:0:x", "spinner:1:2", "coin", "mouse:0", ""] {
+        assert_eq!(Source::parse(name), None, "{:?}", name);
+    }
+}
+
+#[test]
+fn buttons_latch_pressed_and_released_for_one_frame() {
+    let mut input = InputMap::default();
+    input.bind(key("KeyZ"), "fire", 1.0);
+
+    input.set_button(key("KeyZ"), true);
+    input.update();
+    let state = input.action("fire");
+    assert!(state.down && state.pressed && !state.released);
+    assert_eq!(state.value, 1.0);
+
+    input.update();
+    let state = input.action("fire");
+    assert!(state.down && !state.pressed && !state.released);
+
+    input.set_button(key("KeyZ"), false);
+    input.update();
+    let state = input.action("fire");
+    assert!(!state.down && !state.pressed && state.released);
+    assert_eq!(state.value, 0.0);
+
+    input.update();
+    assert!(!input.action("fire").released);
+}
+
+#[test]
+fn coin_pulses_shorter_than_a_frame_still_register() {
+    let mut input = InputMap::default();
+    input.bind(Source::Coin(0), "credit", 1.0);
+
+    input.set_button(Source::Coin(0), true);
+    input.set_button(Source::Coin(0), false);
+    input.update();
+    let state = input.action("credit");
+    assert!(state.pressed && !state.down);
+
+    input.update();
+    assert!(!input.action("credit").pressed);
+}
+
+#[test]
+fn axes_below_the_deadzone_read_as_rest() {
+    let mut input = InputMap::default();
+    let stick = Source::GamepadAxis {pad: 0, axis: 0};
+    input.bind(stick.clone(), "turn", 1.0);
+
+    input.set_axis(stick.clone(), 0.1);
+    input.update();
+    assert_eq!(input.action("turn").value, 0.0);
+    assert!(!input.action("turn").down);
+
+    input.set_axis(stick.clone(), -0.6);
+    input.update();
+    assert_eq!(input.action("turn").value, -0.6);
+
+    input.axis_deadzone = 0.7;
+    input.update();
+    assert_eq!(input.action("turn").value, 0.0);
+}
+
+#[test]
+fn bindings_combine_and_clamp_while_spinners_add_on_top() {
+    let mut input = InputMap::default();
+    input.bind(key("ArrowRight"), "turn", 1.0);
+    input.bind(key("KeyD"), "turn", 1.0);
+    input.bind(key("ArrowLeft"), "turn", -1.0);
+    input.bind(Source::Spinner(0), "turn", 0.5);
+
+    input.set_button(key("ArrowRight"), true);
+    input.set_button(key("KeyD"), true);
+    input.add_delta(Source::Spinner(0), 3.0);
+    input.add_delta(Source::Spinner(0), 1.0);
+    input.update();
+    assert_eq!(input.action("turn").value, 1.0 + 2.0);
+
+    // Spinner movement is only counted in the frame it arrived
+    input.update();
+    assert_eq!(input.action("turn").value, 1.0);
+
+    input.set_button(key("ArrowLeft"), true);
+    input.update();
+    assert_eq!(input.action("turn").value, 1.0);
+}
+
+#[test]
+fn unbinding_removes_every_binding_of_a_source() {
+    let mut input = InputMap::default();
+    input.bind(key("Space"), "fire", 1.0);
+    input.bind(key("Space"), "jump", 1.0);
+
+    assert!(input.unbind(&key("Space")));
+    assert!(!input.unbind(&key("Space")));
+
+    input.set_button(key("Space"), true);
+    input.update();
+    assert!(!input.action("fire").down && !input.action("jump").down);
+    assert_eq!(input.action("unknown").value, 0.0);
+}
@@ -0,0 +1,146 @@
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+
+use vector_demo::eng::euler::*;
+use vector_demo::eng::renderer::{Camera, Renderer};
+
+fn assert_matrix_eq(actual: Mat4, expected: Mat4, tolerance: f64) {
+    for idx in 0..16 {
+        assert!((actual.elems[idx] - expected.elems[idx]).abs() <= tolerance,
+            "element {} differs: {:?} vs {:?}", idx, actual, expected);
+    }
+}
+
+fn rigid_transform() -> Mat4 {
+    return translation_matrix(3.0, -2.0, 7.5) * axis_angle_rotation_matrix(Vec3::new(1.0, 2.0, -0.5), 1.1);
+}
+
+#[test]
+fn inverse_of_known_matrix() {
+    let matrix = Mat4::new([
+        2.0, 0.0, 0.0, 1.0,
+        0.0, 4.0, 0.0, 2.0,
+        0.0, 0.0, 8.0, 3.0,
+        0.0, 0.0, 0.0, 1.0,
+    ]);
+    let expected = Mat4::new([
+        0.5, 0.0,  0.0,   -0.5,
+        0.0, 0.25, 0.0,   -0.5,
+        0.0, 0.0,  0.125, -0.375,
+        0.0, 0.0,  0.0,    1.0,
+    ]);
+
+    assert_matrix_eq(matrix.inverse(), expected, 1e-12);
+    assert_matrix_eq(matrix.try_inverse(INVERSE_EPSILON).unwrap(), expected, 1e-12);
+    assert_matrix_eq(matrix.try_affine_inverse(INVERSE_EPSILON).unwrap(), expected, 1e-12);
+    assert!((matrix.determinant() - 64.0).abs() < 1e-12);
+}
+
+#[test]
+fn inverse_of_rotation_is_transpose() {
+    let rotation = z_rotation_matrix(std::f64::consts::FRAC_PI_2);
+    let expected = z_rotation_matrix(-std::f64::consts::FRAC_PI_2);
+
+    assert_matrix_eq(rotation.inverse(), expected, 1e-12);
+    assert_matrix_eq(rotation.rigid_inverse(), expected, 1e-12);
+}
+
+#[test]
+fn singular_matrices_have_no_inverse() {
+    let flattened = scale_matrix(1.0, 0.0, 1.0);
+    let repeated_row = Mat4::new([
+        1.0, 2.0, 3.0, 4.0,
+        1.0, 2.0, 3.0, 4.0,
+        0.0, 0.0, 1.0, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+    ]);
+
+    assert!(flattened.try_inverse(INVERSE_EPSILON).is_none());
+    assert!(flattened.try_affine_inverse(INVERSE_EPSILON).is_none());
+    assert!(repeated_row.try_inverse(INVERSE_EPSILON).is_none());
+    assert!(Mat4::default().try_inverse(0.0).is_none());
+}
+
+#[test]
+fn epsilon_is_configurable() {
+    let tiny = scale_matrix(1e-3, 1e-3, 1e-3);
+
+    assert!(tiny.try_inverse(INVERSE_EPSILON).is_some());
+    assert!(tiny.try_inverse(1e-6).is_none());
+}
+
+#[test]
+fn fast_inverses_match_general_inverse() {
+    let rigid = rigid_transform();
+    let affine = rigid * scale_matrix(2.0, 0.5, 3.0);
+
+    assert_matrix_eq(rigid.rigid_inverse(), rigid.inverse(), 1e-12);
+    assert_matrix_eq(affine.try_affine_inverse(INVERSE_EPSILON).unwrap(), affine.inverse(), 1e-12);
+    assert_matrix_eq(rigid * rigid.rigid_inverse(), IDENTITY4X4, 1e-12);
+}
+
+#[test]
+fn affine_inverse_rejects_projections() {
+    let renderer = Renderer::default();
+
+    assert!(!renderer.PROJECTION_MATRIX.is_affine());
+    assert!(renderer.PROJECTION_MATRIX.try_affine_inverse(INVERSE_EPSILON).is_none());
+    assert!(renderer.PROJECTION_MATRIX.try_inverse(INVERSE_EPSILON).is_some());
+}
+
+#[test]
+fn camera_view_matrix_inverts_rigidly() {
+    let camera = Camera::new(Vec3::new(1.0, 2.0, -30.0), y_rotation_matrix(0.3));
+    let view = camera.view_matrix();
+
+    assert_matrix_eq(view.rigid_inverse(), view.inverse(), 1e-12);
+}
+
+// The eye must sit at `position` however the camera is turned
+#[test]
+fn camera_view_matrix_puts_the_eye_at_the_position() {
+    let orientation = y_rotation_matrix(0.6) * x_rotation_matrix(-0.25) * z_rotation_matrix(1.3);
+    let camera = Camera::new(Vec3::new(3.0, -2.0, -10.0), orientation);
+    let view = camera.view_matrix();
+
+    let eye = view.rigid_inverse().transform_point(Vec3::default());
+    assert!((eye - camera.position).length() < 1e-12, "eye at {}", eye);
+
+    // A rear-view camera yawed half a turn still sits behind the origin, looking back at it
+    let rear = Camera::new(Vec3::new(0.0, 0.0, -200.0), y_rotation_matrix(std::f64::consts::PI));
+    let origin = rear.view_matrix().transform_point(Vec3::default());
+    assert!((origin - Vec3::new(0.0, 0.0, -200.0)).length() < 1e-9, "origin seen at {}", origin);
+}
+
+#[test]
+fn normal_matrix_keeps_normals_perpendicular() {
+    let transform = axis_angle_rotation_matrix(Vec3::new(0.0, 1.0, 1.0), 0.7) * scale_matrix(1.0, 4.0, 0.5);
+    let normals = transform.normal_matrix(INVERSE_EPSILON).unwrap();
+
+    // A diagonal surface and its normal
+    let tangent = Vec3::new(1.0, -1.0, 0.0);
+    let normal = Vec3::new(1.0, 1.0, 0.0);
+
+    assert!((transform.transform_direction(tangent).dot(normals * normal)).abs() < 1e-12);
+}
+
+#[test]
+fn normal_matrix_of_a_flattened_transform_is_none() {
+    let flattened = y_rotation_matrix(0.4) * scale_matrix(2.0, 0.0, 1.0);
+
+    assert!(flattened.normal_matrix(INVERSE_EPSILON).is_none());
+}
+
+#[test]
+fn only_rotations_are_orthonormal() {
+    assert!(rigid_transform().upper_left().is_orthonormal());
+    assert!(!scale_matrix(1.0, 2.0, 1.0).upper_left().is_orthonormal());
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "not a rotation")]
+fn rigid_inverse_rejects_scaled_matrices_in_debug_builds() {
+    scale_matrix(1.0, 2.0, 1.0).rigid_inverse();
+}
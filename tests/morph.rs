@@ -0,0 +1,61 @@
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+
+use vector_demo::eng::euler::{Vec3, IDENTITY4X4};
+use vector_demo::eng::morph::Morph;
+use vector_demo::eng::renderer::{make_cube, make_skeleton1, make_sphere, GameObject};
+
+fn no_lines() -> GameObject {
+    let points = vec![Vec3::default()];
+    return GameObject::new(Vec3::default(), Vec3::default(), IDENTITY4X4, IDENTITY4X4, points, Vec::new());
+}
+
+fn same_points(a: &[Vec3], b: &[Vec3]) -> bool {
+    return a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| (*a - *b).length() == 0.0);
+}
+
+#[test]
+fn targets_with_the_same_topology_are_used_as_they_are() {
+    let mut object = make_cube(1.0);
+    let mut morph = Morph::new(0, &object);
+
+    assert_eq!(morph.add_shape(&mut object, make_cube(3.0), 16), Some(0));
+    assert_eq!(object.connections, make_cube(1.0).connections);
+    assert!(same_points(&morph.targets[0], &make_cube(3.0).points));
+}
+
+#[test]
+fn every_target_is_resampled_together() {
+    let mut object = make_cube(1.0);
+    let mut morph = Morph::new(0, &object);
+
+    assert_eq!(morph.add_shape(&mut object, make_skeleton1(1.0), 16), Some(0));
+    morph.set_weight(0, 0.5);
+    assert_eq!(morph.add_shape(&mut object, make_sphere(1.0, 4, 8), 16), Some(1));
+    assert_eq!(morph.add_shape(&mut object, make_cube(2.0), 16), Some(2));
+
+    // The sphere has more edges than 16, so everything now has as many segments as it does
+    assert!(object.connections.len() > 32);
+    assert_eq!(object.points.len(), object.connections.len());
+    for target in morph.targets.iter() {
+        assert_eq!(target.len(), morph.base.len());
+    }
+
+    // Weights survive resampling and the object shows the blend
+    assert_eq!(morph.weights, vec![0.5, 0.0, 0.0]);
+    assert!(same_points(&object.points, &morph.blend()));
+}
+
+#[test]
+fn a_target_without_segments_changes_nothing() {
+    let mut object = make_cube(1.0);
+    let mut morph = Morph::new(0, &object);
+    assert_eq!(morph.add_shape(&mut object, make_skeleton1(1.0), 16), Some(0));
+    let (points, connections) = (object.points.clone(), object.connections.clone());
+
+    assert_eq!(morph.add_shape(&mut object, no_lines(), 16), None);
+    assert_eq!(morph.targets.len(), 1);
+    assert!(same_points(&object.points, &points));
+    assert_eq!(object.connections, connections);
+}
@@ -0,0 +1,77 @@
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+
+use vector_demo::eng::euler::*;
+use vector_demo::eng::renderer::{make_cube, make_shape, GameObject};
+use vector_demo::eng::Error;
+
+const SQUARE: &str = "
+# A unit square
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+";
+
+#[test]
+fn lines_and_faces_become_connections() {
+    let object = GameObject::from_obj(&format!("{}l 1 2 3\n", SQUARE)).unwrap();
+    assert_eq!(object.points.len(), 4);
+    assert_eq!(object.connections, vec![0, 1, 1, 2]);
+
+    // Faces are closed back to their first point, and texture and normal indices are ignored
+    let object = GameObject::from_obj(&format!("{}vt 0 0\nf 1/1/1 2/1/1 3//1 4\n", SQUARE)).unwrap();
+    assert_eq!(object.connections, vec![0, 1, 1, 2, 2, 3, 3, 0]);
+}
+
+#[test]
+fn negative_indices_count_back_from_the_last_point() {
+    let object = GameObject::from_obj(&format!("{}l -1 -4\nv 2 2 2\nl -1 -2\n", SQUARE)).unwrap();
+
+    assert_eq!(object.connections, vec![3, 0, 4, 3]);
+    assert_eq!(object.points[4][2], 2.0);
+}
+
+#[test]
+fn errors_name_the_offending_line() {
+    // Line 1 is empty, the square's comment is line 2
+    let cases = [
+        (format!("{}l 1 5\n", SQUARE), 7),
+        (format!("{}l 0 1\n", SQUARE), 7),
+        (format!("{}l -5 1\n", SQUARE), 7),
+        (format!("{}f 2\n", SQUARE), 7),
+        (String::from("v 1 2\n"), 1),
+        (String::from("v 1 x 2\n"), 1),
+        (format!("{}l 1 two\n", SQUARE), 7),
+    ];
+
+    for (source, line) in cases.iter() {
+        match GameObject::from_obj(source) {
+            Err(Error::Parse {line: actual, ..}) => assert_eq!(actual, *line, "{}", source),
+            other => panic!("expected a parse error for {:?}, got {:?}", source, other.map(|object| object.connections)),
+        }
+    }
+}
+
+#[test]
+fn validate_rejects_broken_connections() {
+    assert_eq!(make_cube(1.0).validate(), Ok(()));
+
+    let mut odd = make_cube(1.0);
+    odd.connections.push(0);
+    assert_eq!(odd.validate(), Err(Error::OddConnectionCount(25)));
+
+    let points = vec![Vec3::default(), Vec3::new(1.0, 0.0, 0.0)];
+    let dangling = GameObject::new(Vec3::default(), Vec3::default(), IDENTITY4X4, IDENTITY4X4, points, vec![0, 1, 1, 2]);
+    assert_eq!(dangling.validate(), Err(Error::InvalidConnection {connection: 3, index: 2, point_count: 2}));
+}
+
+#[test]
+fn unknown_shapes_are_reported_by_name() {
+    assert!(make_shape("cube", 1.0).is_ok());
+
+    let error = make_shape("dodecahedron", 1.0).unwrap_err();
+    assert_eq!(error, Error::UnknownShape(String::from("dodecahedron")));
+    assert_eq!(error.to_string(), "unknown shape \"dodecahedron\"");
+}
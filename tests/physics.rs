@@ -0,0 +1,175 @@
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+
+use vector_demo::eng::collision::Contact;
+use vector_demo::eng::euler::Vec3;
+use vector_demo::eng::physics::{Integrator, PhysicsWorld, RigidBody};
+use vector_demo::eng::renderer::{make_cube, GameObject};
+
+fn body_at_rest() -> GameObject {
+    let mut cube = make_cube(2.0);
+    cube.body = Some(RigidBody::new(2.0, 1.0, 0.5, 0.0, 0.0));
+    return cube;
+}
+
+// An impulse of 4 on a mass of 2 must leave the body moving at 2 units per tick, i.e. 2 * dt
+// per step, whatever the timestep
+fn check_impulse(integrator: Integrator) {
+    let world = PhysicsWorld::new(Vec3::default(), 0.25, integrator);
+    let mut objects = vec![body_at_rest()];
+
+    // Lets Verlet record a previous position before the impulse arrives
+    world.step(&mut objects);
+    world.apply_impulse(&mut objects[0], Vec3::new(4.0, 0.0, 0.0), Vec3::default());
+
+    for _ in 0..3 {
+        let before = objects[0].position;
+        world.step(&mut objects);
+
+        let moved = objects[0].position - before;
+        assert!((moved[0] - 0.5).abs() < 1e-12, "{:?} moved {:?}", integrator, moved);
+        assert!((objects[0].velocity[0] - 2.0).abs() < 1e-12, "{:?} velocity {:?}", integrator, objects[0].velocity);
+    }
+}
+
+#[test]
+fn impulse_sets_velocity_with_euler() {
+    check_impulse(Integrator::SemiImplicitEuler);
+}
+
+#[test]
+fn impulse_sets_velocity_with_verlet() {
+    check_impulse(Integrator::Verlet);
+}
+
+// Falling from rest, each step adds g * dt to the velocity and the body covers
+// g * dt^2 * n (n + 1) / 2 after n steps under either integrator
+fn check_gravity(integrator: Integrator) {
+    let world = PhysicsWorld::new(Vec3::new(0.0, -2.0, 0.0), 0.5, integrator);
+    let mut objects = vec![body_at_rest()];
+
+    for n in 1..=4 {
+        world.step(&mut objects);
+
+        let steps = n as f64;
+        let fallen = -2.0 * 0.25 * steps * (steps + 1.0) / 2.0;
+        assert!((objects[0].velocity[1] + 2.0 * 0.5 * steps).abs() < 1e-12, "{:?} velocity {:?}", integrator, objects[0].velocity);
+        assert!((objects[0].position[1] - fallen).abs() < 1e-12, "{:?} position {:?}", integrator, objects[0].position);
+    }
+}
+
+#[test]
+fn gravity_accelerates_with_euler() {
+    check_gravity(Integrator::SemiImplicitEuler);
+}
+
+#[test]
+fn gravity_accelerates_with_verlet() {
+    check_gravity(Integrator::Verlet);
+}
+
+// A damping of 0.25 keeps three quarters of the velocity every step
+fn check_damping(integrator: Integrator) {
+    let world = PhysicsWorld::new(Vec3::default(), 1.0, integrator);
+    let mut objects = vec![body_at_rest()];
+    objects[0].body = Some(RigidBody::new(2.0, 1.0, 0.5, 0.25, 0.0));
+    objects[0].velocity = Vec3::new(4.0, 0.0, 0.0);
+
+    let mut expected = 4.0;
+    for _ in 0..3 {
+        world.step(&mut objects);
+        expected *= 0.75;
+        assert!((objects[0].velocity[0] - expected).abs() < 1e-12, "{:?} velocity {:?}", integrator, objects[0].velocity);
+    }
+}
+
+#[test]
+fn damping_slows_with_euler() {
+    check_damping(Integrator::SemiImplicitEuler);
+}
+
+#[test]
+fn damping_slows_with_verlet() {
+    check_damping(Integrator::Verlet);
+}
+
+// Two unit masses at x = 0 and x = 3 heading towards each other at 1 unit per tick, touching
+// 0.5 deep along +x
+fn head_on(restitution: f64) -> (Vec<GameObject>, Contact) {
+    let mut left = make_cube(2.0);
+    left.body = Some(RigidBody::new(1.0, 1.0, restitution, 0.0, 0.0));
+    left.velocity = Vec3::new(1.0, 0.0, 0.0);
+
+    let mut right = make_cube(2.0);
+    right.body = Some(RigidBody::new(1.0, 1.0, restitution, 0.0, 0.0));
+    right.position = Vec3::new(3.0, 0.0, 0.0);
+    right.velocity = Vec3::new(-1.0, 0.0, 0.0);
+
+    let contact = Contact {
+        a: 0,
+        b: 1,
+        point: Vec3::new(1.5, 0.0, 0.0),
+        normal: Vec3::new(1.0, 0.0, 0.0),
+        depth: 0.5,
+    };
+
+    return (vec![left, right], contact);
+}
+
+#[test]
+fn elastic_bodies_swap_velocities() {
+    let world = PhysicsWorld::default();
+    let (mut objects, contact) = head_on(1.0);
+
+    world.resolve(&mut objects, &[contact]);
+
+    assert!((objects[0].velocity[0] + 1.0).abs() < 1e-12, "{:?}", objects[0].velocity);
+    assert!((objects[1].velocity[0] - 1.0).abs() < 1e-12, "{:?}", objects[1].velocity);
+}
+
+#[test]
+fn restitution_scales_the_separating_speed() {
+    let world = PhysicsWorld::default();
+
+    for &restitution in &[0.0, 0.5] {
+        let (mut objects, contact) = head_on(restitution);
+        world.resolve(&mut objects, &[contact]);
+
+        let separating = (objects[1].velocity - objects[0].velocity)[0];
+        assert!((separating - 2.0 * restitution).abs() < 1e-12, "restitution {} separates at {}", restitution, separating);
+    }
+}
+
+#[test]
+fn resolve_pushes_overlapping_bodies_apart() {
+    let world = PhysicsWorld::default();
+    let (mut objects, contact) = head_on(0.5);
+    objects[0].velocity = Vec3::default();
+    objects[1].velocity = Vec3::default();
+
+    world.resolve(&mut objects, &[contact]);
+
+    // Equal masses share the correction of 0.8 * (0.5 - 0.01) evenly
+    let correction = 0.8 * (0.5 - 0.01) / 2.0;
+    assert!((objects[0].position[0] + correction).abs() < 1e-12, "{:?}", objects[0].position);
+    assert!((objects[1].position[0] - 3.0 - correction).abs() < 1e-12, "{:?}", objects[1].position);
+
+    // Correction only moves them, it does not set them flying
+    assert!(objects[0].velocity.length() < 1e-12);
+    assert!(objects[1].velocity.length() < 1e-12);
+}
+
+#[test]
+fn resolve_leaves_static_bodies_in_place() {
+    let world = PhysicsWorld::default();
+    let (mut objects, contact) = head_on(0.5);
+    objects[1].body = Some(RigidBody::new(0.0, 1.0, 0.5, 0.0, 0.0));
+    objects[1].velocity = Vec3::default();
+
+    world.resolve(&mut objects, &[contact]);
+
+    assert!((objects[1].position[0] - 3.0).abs() < 1e-12, "{:?}", objects[1].position);
+    assert!(objects[0].position[0] < 0.0, "{:?}", objects[0].position);
+    assert!((objects[0].velocity[0] + 0.5).abs() < 1e-12, "{:?}", objects[0].velocity);
+}
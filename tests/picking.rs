@@ -0,0 +1,60 @@
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+
+use vector_demo::eng::euler::*;
+use vector_demo::eng::picking::{pick, Ray};
+use vector_demo::eng::renderer::{make_cube, Camera, GameObject, Renderer};
+
+// A point given in the camera's own axes, e.g. straight ahead along +z
+fn in_front_of(camera: &Camera, camera_space: Vec3) -> Vec3 {
+    return camera.position + camera.orientation.transform_direction(camera_space);
+}
+
+fn rotated_camera() -> Camera {
+    let orientation = y_rotation_matrix(0.6) * x_rotation_matrix(-0.25);
+    return Camera::new(Vec3::new(3.0, -2.0, -10.0), orientation);
+}
+
+// Turned with the camera, so its front face is square on to the view
+fn cube_at(camera: &Camera, camera_space: Vec3) -> GameObject {
+    let mut cube = make_cube(4.0);
+    cube.position = in_front_of(camera, camera_space);
+    cube.orientation = camera.orientation;
+    return cube;
+}
+
+#[test]
+fn picks_the_object_at_the_screen_centre() {
+    let mut renderer = Renderer::default();
+    let camera = rotated_camera();
+    let objects = vec![
+        cube_at(&camera, Vec3::new(30.0, 0.0, 50.0)),
+        cube_at(&camera, Vec3::new(0.0, 0.0, 50.0)),
+    ];
+
+    let ray = Ray::from_screen(&renderer, &camera, 0.0, 0.0).unwrap();
+    let hit = pick(&objects, &ray).expect("the centre object should be hit");
+    assert_eq!(hit.object_id, 1);
+    assert!((hit.distance - 48.0).abs() < 1e-6, "{:?}", hit);
+
+    // The side object is drawn at x = 30 / 50 and can be picked there
+    let ray = Ray::from_screen(&renderer, &camera, 0.6, 0.0).unwrap();
+    assert_eq!(pick(&objects, &ray).map(|hit| hit.object_id), Some(0));
+
+    // The renderer agrees on where the centre object is
+    let lines = renderer.render_frame(&objects[1..], &camera);
+    let (mean_x, mean_y) = lines.chunks(2).fold((0.0, 0.0), |(x, y), point| (x + point[0], y + point[1]));
+    let count = (lines.len() / 2) as f64;
+    assert!((mean_x / count).abs() < 1e-9 && (mean_y / count).abs() < 1e-9, "{:?}", lines);
+}
+
+#[test]
+fn misses_objects_off_to_the_side() {
+    let renderer = Renderer::default();
+    let camera = rotated_camera();
+    let objects = vec![cube_at(&camera, Vec3::new(30.0, 0.0, 50.0))];
+
+    let ray = Ray::from_screen(&renderer, &camera, 0.0, 0.0).unwrap();
+    assert!(pick(&objects, &ray).is_none());
+}
@@ -0,0 +1,112 @@
+#![allow(clippy::needless_return)]
+#![cfg(not(target_arch = "wasm32"))]
+
+extern crate proptest;
+extern crate vector_demo;
+
+use proptest::prelude::*;
+
+use vector_demo::eng::batch::project_points_scalar;
+use vector_demo::eng::euler::*;
+use vector_demo::eng::renderer::{make_cube, Camera, GameObject, Renderer};
+
+fn single_segment(tail: Vec3, head: Vec3) -> GameObject {
+    return GameObject::new(Vec3::default(), Vec3::default(), IDENTITY4X4, IDENTITY4X4, vec![tail, head], vec![0, 1]);
+}
+
+#[test]
+fn projects_known_points() {
+    // With a 90 degree field of view and a square aspect ratio, x / z and y / z land directly on
+    // the screen
+    let mut renderer = Renderer::default();
+    let camera = Camera::new(Vec3::default(), IDENTITY4X4);
+    let object = single_segment(Vec3::new(0.0, 0.0, 10.0), Vec3::new(5.0, -2.5, 10.0));
+
+    let lines = renderer.render_frame(&[object], &camera);
+    assert_eq!(lines.len(), 4);
+    for (actual, expected) in lines.iter().zip([0.0, 0.0, 0.5, -0.25].iter()) {
+        assert!((actual - expected).abs() < 1e-12, "{:?}", lines);
+    }
+}
+
+#[test]
+fn aspect_ratio_scales_x() {
+    let mut renderer = Renderer::default();
+    renderer.update_aspect_ratio(0.5);
+    let camera = Camera::new(Vec3::default(), IDENTITY4X4);
+    let object = single_segment(Vec3::new(0.0, 0.0, 10.0), Vec3::new(10.0, 10.0, 10.0));
+
+    let lines = renderer.render_frame(&[object], &camera);
+    assert!((lines[2] - 0.5).abs() < 1e-12 && (lines[3] - 1.0).abs() < 1e-12, "{:?}", lines);
+}
+
+#[test]
+fn objects_behind_the_camera_are_culled() {
+    let mut renderer = Renderer::default();
+    let camera = Camera::new(Vec3::default(), IDENTITY4X4);
+    let mut behind = make_cube(2.0);
+    behind.position = Vec3::new(0.0, 0.0, -20.0);
+    let mut ahead = make_cube(2.0);
+    ahead.position = Vec3::new(0.0, 0.0, 20.0);
+
+    assert!(renderer.render_frame(&[behind], &camera).is_empty());
+    assert_eq!(renderer.render_frame(&[ahead], &camera).len(), 12 * 4);
+}
+
+#[test]
+fn frame_stats_count_what_was_drawn() {
+    let mut renderer = Renderer::default();
+    let camera = Camera::new(Vec3::default(), IDENTITY4X4);
+    let mut behind = make_cube(2.0);
+    behind.position = Vec3::new(0.0, 0.0, -20.0);
+    let mut ahead = make_cube(2.0);
+    ahead.position = Vec3::new(0.0, 0.0, 20.0);
+    // Reaches from the middle of the screen to twice its right edge
+    let wide = single_segment(Vec3::new(0.0, 0.0, 10.0), Vec3::new(20.0, 0.0, 10.0));
+
+    let (lines, stats) = renderer.render_frame_stats::<f64>(&[behind, ahead, wide], &camera);
+    assert_eq!(lines.len(), 13 * 4);
+    assert_eq!((stats.objects_rendered, stats.objects_culled), (2, 1));
+    assert_eq!(stats.points_transformed, 8 + 2);
+    assert_eq!((stats.segments_emitted, stats.segments_offscreen), (13, 1));
+
+    let cube_edges: f64 = lines[..12 * 4].chunks(4).map(|line| (line[2] - line[0]).hypot(line[3] - line[1])).sum();
+    assert!((stats.line_length - (cube_edges + 2.0)).abs() < 1e-9, "{:?}", stats);
+}
+
+#[test]
+fn single_precision_stays_close() {
+    let mut renderer = Renderer::default();
+    let camera = Camera::default();
+    let mut cube = make_cube(50.0);
+    cube.orientation = axis_angle_rotation_matrix(Vec3::new(1.0, 1.0, 0.0), 0.6);
+
+    let full = renderer.render_frame(&[cube.clone()], &camera);
+    let single = renderer.render_frame_with::<f32>(&[cube], &camera);
+
+    assert_eq!(full.len(), single.len());
+    for (a, b) in full.iter().zip(single.iter()) {
+        assert!((a - *b as f64).abs() < 1e-5);
+    }
+}
+
+proptest! {
+    #[test]
+    fn batch_projection_matches_matrix_chain(
+        x in -50.0..50.0f64, y in -50.0..50.0f64, z in 5.0..500.0f64, theta in -3.0..3.0f64,
+    ) {
+        let renderer = Renderer::default();
+        let model = translation_matrix(1.0, 2.0, 3.0) * y_rotation_matrix(theta);
+        let view = Camera::default().view_matrix();
+        let point = Vec3::new(x, y, z);
+
+        let mut batched: Vec<f64> = Vec::new();
+        project_points_scalar(&(renderer.PROJECTION_MATRIX * view * model), &[point], &mut batched);
+
+        let chained = renderer.PROJECTION_MATRIX * (view * (model * Vec4::new(x, y, z, 1.0)));
+        prop_assume!(chained[3].abs() > 1e-3);
+
+        prop_assert!((batched[0] - chained[0] / chained[3]).abs() <= 1e-9 * (1.0 + batched[0].abs()));
+        prop_assert!((batched[1] - chained[1] / chained[3]).abs() <= 1e-9 * (1.0 + batched[1].abs()));
+    }
+}
@@ -0,0 +1,39 @@
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+
+use vector_demo::eng::beam::unoptimized_path;
+use vector_demo::eng::euler::*;
+use vector_demo::eng::renderer::{Camera, GameObject, Renderer};
+use vector_demo::eng::scope::{synthesize, ScopeSettings};
+
+// One end in front of the camera, the other level with it and then behind it; the object
+// straddles the near plane, so it isn't culled
+#[test]
+fn segments_reaching_behind_the_camera_stay_bounded() {
+    let mut renderer = Renderer::default();
+    let camera = Camera::new(Vec3::default(), IDENTITY4X4);
+    let points = vec![Vec3::new(0.5, 0.0, 10.0), Vec3::new(1.0, 0.5, 0.0), Vec3::new(0.0, 1.0, -10.0)];
+    let object = GameObject::new(Vec3::default(), Vec3::default(), IDENTITY4X4, IDENTITY4X4, points, vec![0, 1, 0, 2]);
+
+    let lines = renderer.render_frame(&[object], &camera);
+    assert_eq!(lines.len(), 8);
+
+    let settings = ScopeSettings::default();
+    let samples = synthesize(&unoptimized_path(&lines), &settings);
+
+    // The most the output square can need: a few passes of its diagonal at this density
+    assert!(samples.len() < 8 * (settings.points_per_unit as usize) * 4, "{} samples", samples.len());
+    assert!(samples.iter().all(|sample| sample.is_finite() && sample.abs() <= 1.0));
+}
+
+#[test]
+fn segments_inside_the_square_are_traced_at_the_set_density() {
+    let settings = ScopeSettings::default();
+
+    // Blank to (-0.5, 0), then draw a unit line to (0.5, 0) and blank home
+    let samples = synthesize(&[-0.5, 0.0, 0.0, 0.5, 0.0, 1.0], &settings);
+    let traced = settings.points_per_unit as usize;
+
+    assert_eq!(samples.len() / 2, (1 + settings.dwell_samples) + traced + (1 + settings.dwell_samples));
+}
@@ -0,0 +1,47 @@
+//! Checks the SIMD projection against the scalar loop it replaces. It only exists in wasm builds
+//! with SIMD enabled, e.g.
+//! CARGO_TARGET_WASM32_UNKNOWN_UNKNOWN_RUSTFLAGS="-C target-feature=+simd128" wasm-pack test --node -- --test simd
+
+#![cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+extern crate wasm_bindgen_test;
+
+use wasm_bindgen_test::*;
+
+use vector_demo::eng::batch::{project_points_scalar, BatchProject};
+use vector_demo::eng::euler::{y_rotation_matrix, Mat4, Scalar, Vec3};
+use vector_demo::eng::renderer::{make_sphere, Camera, Renderer};
+
+// A perspective projection of a turned, offset camera, so every matrix entry is in play
+fn mvp<T: Scalar>() -> Mat4<T> {
+    let renderer = Renderer::default();
+    let camera = Camera::new(Vec3::new(0.3, -0.2, -4.0), y_rotation_matrix(0.4));
+    return (renderer.PROJECTION_MATRIX * camera.view_matrix()).cast::<T>();
+}
+
+fn compare<T: BatchProject>(tolerance: f64) {
+    let matrix = mvp::<T>();
+    let points = make_sphere(1.5, 7, 12).points;
+
+    let (mut simd, mut scalar) = (Vec::<T>::new(), Vec::<T>::new());
+    T::project_points(&matrix, &points, &mut simd);
+    project_points_scalar(&matrix, &points, &mut scalar);
+
+    assert_eq!(simd.len(), scalar.len());
+    for (idx, (a, b)) in simd.iter().zip(scalar.iter()).enumerate() {
+        let (a, b) = (a.to_f64(), b.to_f64());
+        assert!((a - b).abs() <= tolerance * b.abs().max(1.0), "value {}: simd {} scalar {}", idx, a, b);
+    }
+}
+
+#[wasm_bindgen_test]
+fn simd_matches_scalar_in_f64() {
+    compare::<f64>(1e-12);
+}
+
+#[wasm_bindgen_test]
+fn simd_matches_scalar_in_f32() {
+    compare::<f32>(1e-5);
+}
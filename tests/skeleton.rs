@@ -0,0 +1,92 @@
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+
+use std::f64::consts::FRAC_PI_2;
+
+use vector_demo::eng::euler::{Quat, Vec3};
+use vector_demo::eng::renderer::make_skeleton1;
+use vector_demo::eng::skeleton::make_stick_figure;
+
+fn assert_near(actual: Vec3, expected: Vec3) {
+    assert!((actual - expected).length() < 1e-9, "expected {}, got {}", expected, actual);
+}
+
+#[test]
+fn rotating_a_bone_swings_its_own_segment() {
+    let mut figure = make_stick_figure(1.0);
+    let rest = figure.forward_kinematics();
+    let (shoulder, upper_arm, forearm) = (
+        figure.bone_index("left_shoulder").unwrap(),
+        figure.bone_index("left_upper_arm").unwrap(),
+        figure.bone_index("left_forearm").unwrap());
+
+    // Raise the upper arm straight out to the side: it turns about the shoulder joint, and the
+    // forearm follows without bending at the elbow
+    figure.set_rotation("left_upper_arm", Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), -FRAC_PI_2));
+    let pose = figure.forward_kinematics();
+
+    assert_near(pose.joints[shoulder], rest.joints[shoulder]);
+    assert_near(pose.joints[upper_arm], rest.joints[shoulder] + Vec3::new(-1.0, 0.0, 0.0));
+    assert_near(pose.joints[forearm], rest.joints[shoulder] + Vec3::new(-2.0, 0.0, 0.0));
+}
+
+#[test]
+fn ik_reaches_a_reachable_target_on_the_pole_side() {
+    let mut figure = make_stick_figure(1.0);
+    let shoulder = figure.forward_kinematics().joints[figure.bone_index("left_shoulder").unwrap()];
+    let target = shoulder + Vec3::new(0.0, 0.5, 1.2);
+    let pole = shoulder + Vec3::new(-5.0, 0.0, 0.0);
+
+    assert!(figure.solve_two_bone_ik("left_forearm", target, pole));
+    let pose = figure.forward_kinematics();
+
+    let elbow = pose.joints[figure.bone_index("left_upper_arm").unwrap()];
+    let hand = pose.joints[figure.bone_index("left_forearm").unwrap()];
+    assert_near(hand, target);
+    assert!(((elbow - shoulder).length() - 1.0).abs() < 1e-9);
+    assert!(elbow[0] < shoulder[0], "elbow {} bends away from the pole", elbow);
+}
+
+#[test]
+fn ik_stretches_towards_an_unreachable_target() {
+    let mut figure = make_stick_figure(1.0);
+    let shoulder = figure.forward_kinematics().joints[figure.bone_index("left_shoulder").unwrap()];
+
+    assert!(figure.solve_two_bone_ik("left_forearm", shoulder + Vec3::new(0.0, 0.0, 10.0), shoulder));
+    let hand = figure.forward_kinematics().joints[figure.bone_index("left_forearm").unwrap()];
+
+    // The solver stops a hair short of fully straight
+    assert!((hand - (shoulder + Vec3::new(0.0, 0.0, 2.0))).length() < 1e-6, "hand at {}", hand);
+}
+
+#[test]
+fn ik_needs_two_bones_above_the_end() {
+    let mut figure = make_stick_figure(1.0);
+
+    assert!(!figure.solve_two_bone_ik("spine", Vec3::default(), Vec3::default()));
+    assert!(!figure.solve_two_bone_ik("tail", Vec3::default(), Vec3::default()));
+}
+
+#[test]
+fn skeleton1_keeps_the_original_shape() {
+    let shape = make_skeleton1(1.0);
+    let points = [
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(-2.0, -1.0, -1.0),
+        Vec3::new(-1.0, 1.0, 1.0),
+        Vec3::new(1.0, 1.0, -1.0),
+        Vec3::new(2.0, -1.0, 1.0),
+    ];
+
+    assert_eq!(shape.points.len(), points.len());
+    for (actual, expected) in shape.points.iter().zip(points.iter()) {
+        assert_near(*actual, *expected);
+    }
+
+    // Every pair of the five points, once
+    let mut pairs: Vec<(usize, usize)> = shape.connections.chunks(2).map(|pair| (pair[0].min(pair[1]), pair[0].max(pair[1]))).collect();
+    pairs.sort();
+    pairs.dedup();
+    assert_eq!(pairs.len(), 10);
+}
@@ -0,0 +1,30 @@
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+
+use vector_demo::eng::euler::*;
+use vector_demo::eng::renderer::Camera;
+use vector_demo::eng::stereo::{Eye, StereoMode, StereoSettings};
+
+// The eyes of a turned and tilted camera must still only disagree left to right
+#[test]
+fn eyes_of_a_rotated_camera_differ_only_horizontally() {
+    let camera = Camera::new(Vec3::new(3.0, -2.0, -10.0), y_rotation_matrix(0.6) * x_rotation_matrix(-0.25));
+    let stereo = StereoSettings::new(StereoMode::Anaglyph, 6.5, 200.0);
+    let left = stereo.eye_camera(&camera, Eye::Left);
+    let right = stereo.eye_camera(&camera, Eye::Right);
+
+    // Half the baseline each way along the camera's right vector
+    let right_axis = camera.orientation.transform_direction(Vec3::new(1.0, 0.0, 0.0));
+    assert!((right.position - left.position - right_axis * 6.5).length() < 1e-9);
+    assert!(((left.position + right.position) * 0.5 - camera.position).length() < 1e-9);
+
+    for point in [Vec3::new(0.0, 0.0, 0.0), Vec3::new(20.0, -5.0, 40.0), Vec3::new(-7.0, 12.0, 90.0)] {
+        let seen_left = left.view_matrix().transform_point(point);
+        let seen_right = right.view_matrix().transform_point(point);
+        let disparity = seen_left - seen_right;
+
+        assert!((disparity[0] - 6.5).abs() < 1e-9, "{}", disparity);
+        assert!(disparity[1].abs() < 1e-9 && disparity[2].abs() < 1e-9, "{}", disparity);
+    }
+}
@@ -0,0 +1,72 @@
+#![allow(clippy::needless_return)]
+
+extern crate vector_demo;
+
+use vector_demo::eng::viewport::{clip_line, Viewport};
+
+fn assert_segment(actual: Option<(f64, f64, f64, f64)>, expected: (f64, f64, f64, f64)) {
+    let actual = actual.expect("segment was clipped away");
+    let pairs = [(actual.0, expected.0), (actual.1, expected.1), (actual.2, expected.2), (actual.3, expected.3)];
+    assert!(pairs.iter().all(|(a, e)| (a - e).abs() < 1e-12), "expected {:?}, got {:?}", expected, actual);
+}
+
+#[test]
+fn clip_keeps_a_segment_inside() {
+    assert_segment(clip_line(-0.5, -0.25, 0.5, 0.75, -1.0, -1.0, 1.0, 1.0), (-0.5, -0.25, 0.5, 0.75));
+}
+
+#[test]
+fn clip_drops_a_segment_outside() {
+    assert_eq!(clip_line(1.5, -0.5, 2.0, 0.5, -1.0, -1.0, 1.0, 1.0), None);
+    // Outside even though its bounding box overlaps the rectangle
+    assert_eq!(clip_line(0.5, 2.0, 2.0, 0.5, -1.0, -1.0, 1.0, 1.0), None);
+}
+
+#[test]
+fn clip_cuts_a_segment_crossing_one_edge() {
+    assert_segment(clip_line(0.0, 0.0, 2.0, 0.5, -1.0, -1.0, 1.0, 1.0), (0.0, 0.0, 1.0, 0.25));
+    // Direction doesn't matter
+    assert_segment(clip_line(2.0, 0.5, 0.0, 0.0, -1.0, -1.0, 1.0, 1.0), (1.0, 0.25, 0.0, 0.0));
+}
+
+#[test]
+fn clip_cuts_a_segment_crossing_two_edges() {
+    assert_segment(clip_line(-3.0, 0.0, 3.0, 0.0, -1.0, -1.0, 1.0, 1.0), (-1.0, 0.0, 1.0, 0.0));
+    assert_segment(clip_line(-2.0, 0.0, 0.0, 2.0, -1.0, -1.0, 1.0, 1.0), (-1.0, 1.0, -1.0, 1.0));
+    assert_segment(clip_line(-0.5, -2.0, 0.5, 2.0, -1.0, -1.0, 1.0, 1.0), (-0.25, -1.0, 0.25, 1.0));
+}
+
+#[test]
+fn compose_squeezes_and_clips_into_the_viewport() {
+    // The top-right quarter of the output
+    let viewport = Viewport::new(0.0, 0.0, 1.0, 1.0);
+    let mut output = Vec::<f64>::new();
+
+    viewport.compose(&[-1.0, -1.0, 1.0, 1.0, 0.0, 0.0, 3.0, 0.0, 2.0, 2.0, 3.0, 3.0], &mut output);
+
+    assert_eq!(output, vec![0.0, 0.0, 1.0, 1.0, 0.5, 0.5, 1.0, 0.5]);
+}
+
+// Single precision frames are composed without going through an f64 buffer
+#[test]
+fn compose_keeps_the_precision_of_the_frame() {
+    let viewport = Viewport::new(0.0, 0.0, 1.0, 1.0);
+    let mut output = Vec::<f32>::new();
+
+    viewport.compose(&[-1.0f32, -1.0, 1.0, 1.0, 0.0, 0.0, 3.0, 0.0], &mut output);
+    viewport.border(&mut output);
+
+    assert_eq!(&output[..8], &[0.0f32, 0.0, 1.0, 1.0, 0.5, 0.5, 1.0, 0.5]);
+    assert_eq!(output.len(), 8 + 16);
+}
+
+#[test]
+fn viewport_aspect_ratio_keeps_the_picture_unstretched() {
+    assert!(Viewport::default().is_full());
+    assert_eq!(Viewport::default().aspect_ratio(0.75), 0.75);
+
+    // A wide strip across the bottom needs a narrower x scale
+    let strip = Viewport::new(-1.0, -1.0, 2.0, 0.5);
+    assert!(!strip.is_full());
+    assert_eq!(strip.aspect_ratio(1.0), 0.25);
+}
@@ -11,3 +11,14 @@ wasm_bindgen_test_configure!(run_in_browser);
 fn pass() {
     assert_eq!(1 + 1, 2);
 }
+
+// A rig rewrites its object's mesh every frame, so morphing it would mix two topologies
+#[wasm_bindgen_test]
+fn morph_targets_are_refused_on_rigged_objects() {
+    let mut wrapper = vector_demo::GlobalWrapper::new();
+    let figure = wrapper.add_stick_figure(1.0).unwrap();
+    let cube = wrapper.add_shape("cube".to_string(), 1.0, 0.0, 0.0, 0.0).unwrap();
+
+    assert!(wrapper.add_morph_target(figure, "sphere".to_string(), 1.0, 12).is_err());
+    assert!(wrapper.add_morph_target(cube, "sphere".to_string(), 1.0, 12).is_ok());
+}